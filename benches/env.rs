@@ -0,0 +1,39 @@
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use yahtzee::env::{Action, BatchEnv, NUM_ACTIONS};
+
+const BATCH_SIZE: usize = 256;
+
+fn batch_steps(c: &mut Criterion) {
+    let mut group = c.benchmark_group("environment");
+    group.throughput(Throughput::Elements(BATCH_SIZE as u64));
+
+    group.bench_function("batch step with random legal actions", |b| {
+        let mut batch = BatchEnv::new(BATCH_SIZE);
+        batch.reset(0);
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut legal_indexes = Vec::with_capacity(NUM_ACTIONS);
+        b.iter(|| {
+            let actions: Vec<Action> = batch
+                .legal_actions()
+                .chunks(NUM_ACTIONS)
+                .map(|legal| {
+                    legal_indexes.clear();
+                    legal_indexes.extend((0..NUM_ACTIONS).filter(|action| legal[*action]));
+                    let index = *legal_indexes
+                        .choose(&mut rng)
+                        .expect("a move is always legal");
+                    Action::from_index(index).expect("the index is in range")
+                })
+                .collect();
+            batch.step(&actions).expect("the actions are legal").1.len()
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, batch_steps);
+criterion_main!(benches);
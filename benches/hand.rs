@@ -0,0 +1,77 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use yahtzee::hand::{DiceHand, Keep};
+use yahtzee::player::Player;
+use yahtzee::probability;
+use yahtzee::rules::Rules;
+use yahtzee::score::{Category, Score};
+
+fn scoring(c: &mut Criterion) {
+    let hands: Vec<DiceHand> = DiceHand::all().collect();
+    let mut group = c.benchmark_group("score all hands");
+
+    group.bench_function("Player::possible_scores", |b| {
+        let mut player = Player::new("bench".to_owned());
+        b.iter(|| {
+            let mut total = 0;
+            for hand in hands.iter() {
+                player.dice = hand.dice().to_vec();
+                total += player
+                    .possible_scores()
+                    .iter()
+                    .map(Score::value)
+                    .sum::<u32>();
+            }
+            black_box(total)
+        })
+    });
+
+    group.bench_function("DiceHand::score", |b| {
+        b.iter(|| {
+            let mut total = 0;
+            for hand in hands.iter() {
+                total += Category::STANDARD
+                    .iter()
+                    .map(|category| black_box(*hand).score(*category))
+                    .sum::<u32>();
+            }
+            black_box(total)
+        })
+    });
+
+    group.bench_function("DiceHand::from_dice", |b| {
+        let dice: Vec<[u32; 5]> = hands.iter().map(|hand| hand.dice()).collect();
+        b.iter(|| {
+            for dice in dice.iter() {
+                black_box(DiceHand::from_dice(black_box(dice)));
+            }
+        })
+    });
+
+    group.finish();
+}
+
+fn rerolling(c: &mut Criterion) {
+    let dice = [2, 3, 3, 5, 6];
+    let held = [false, true, true, false, false];
+    let mut group = c.benchmark_group("reroll two of a kind");
+
+    group.bench_function("probability::reroll_distribution", |b| {
+        let rules = Rules::default();
+        b.iter(|| {
+            black_box(probability::reroll_distribution(
+                &rules,
+                black_box(&dice),
+                &held,
+            ))
+        })
+    });
+
+    group.bench_function("Keep::outcomes", |b| {
+        b.iter(|| black_box(Keep::from_dice(black_box(&[3, 3])).unwrap().outcomes()))
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, scoring, rerolling);
+criterion_main!(benches);
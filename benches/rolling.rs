@@ -0,0 +1,41 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use yahtzee::player::Player;
+
+fn rolling(c: &mut Criterion) {
+    let mut player = Player::new("bench".to_owned());
+
+    c.bench_function("Player::roll_dice", |b| {
+        b.iter(|| {
+            player.roll_dice();
+            black_box(&player.dice);
+        })
+    });
+
+    c.bench_function("Player::roll_dice_with seeded", |b| {
+        let mut rng = StdRng::seed_from_u64(0);
+        b.iter(|| {
+            player.roll_dice_with(&mut rng);
+            black_box(&player.dice);
+        })
+    });
+
+    c.bench_function("Player::reroll three dice", |b| {
+        b.iter(|| {
+            player.reroll(black_box(vec![1, 3, 5]));
+            black_box(&player.dice);
+        })
+    });
+
+    c.bench_function("Player::reroll_with three dice seeded", |b| {
+        let mut rng = StdRng::seed_from_u64(0);
+        b.iter(|| {
+            player.reroll_with(black_box(vec![1, 3, 5]), &mut rng);
+            black_box(&player.dice);
+        })
+    });
+}
+
+criterion_group!(benches, rolling);
+criterion_main!(benches);
@@ -0,0 +1,104 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use yahtzee::player::Player;
+use yahtzee::score::Score;
+
+type Evaluator = fn(&Player) -> Option<Score>;
+
+/// a spread of hands that hit and miss each category
+const HANDS: [[u32; 5]; 8] = [
+    [1, 2, 3, 4, 5],
+    [2, 3, 4, 5, 5],
+    [3, 3, 3, 5, 5],
+    [6, 6, 6, 6, 2],
+    [4, 4, 4, 4, 4],
+    [1, 1, 3, 5, 6],
+    [2, 2, 2, 1, 6],
+    [1, 3, 4, 6, 6],
+];
+
+fn player_with(dice: [u32; 5]) -> Player {
+    let mut player = Player::new("bench".to_owned());
+    player.dice = dice.to_vec();
+    player
+}
+
+fn possible_scores(c: &mut Criterion) {
+    let mut players: Vec<Player> = HANDS.iter().map(|dice| player_with(*dice)).collect();
+
+    c.bench_function("Player::possible_scores", |b| {
+        b.iter(|| {
+            for player in players.iter_mut() {
+                black_box(player.possible_scores());
+            }
+        })
+    });
+
+    let mut late_game = player_with(HANDS[0]);
+    for score in [
+        Score::Aces(3),
+        Score::Twos(6),
+        Score::Threes(9),
+        Score::FullHouse(25),
+        Score::Chance(22),
+        Score::Yahtzee(50),
+    ] {
+        late_game.update_score(score);
+    }
+    c.bench_function("Player::possible_scores late game", |b| {
+        b.iter(|| black_box(late_game.possible_scores()))
+    });
+}
+
+fn evaluators(c: &mut Criterion) {
+    let players: Vec<Player> = HANDS.iter().map(|dice| player_with(*dice)).collect();
+    let mut group = c.benchmark_group("Score");
+
+    let evaluators: [(&str, Evaluator); 7] = [
+        ("yahtzee", Score::yahtzee),
+        ("large_straight", Score::large_straight),
+        ("small_straight", Score::small_straight),
+        ("three_of_a_kind", Score::three_of_a_kind),
+        ("four_of_a_kind", Score::four_of_a_kind),
+        ("full_house", Score::full_house),
+        ("chance", Score::chance),
+    ];
+    for (name, evaluator) in evaluators.iter() {
+        group.bench_function(*name, |b| {
+            b.iter(|| {
+                for player in players.iter() {
+                    black_box(evaluator(black_box(player)));
+                }
+            })
+        });
+    }
+
+    group.bench_function("upper_score", |b| {
+        b.iter(|| {
+            for player in players.iter() {
+                for die_face in 1..=6 {
+                    black_box(Score::upper_score(black_box(player), die_face));
+                }
+            }
+        })
+    });
+
+    let mut upper_section = player_with(HANDS[0]);
+    for score in [
+        Score::Aces(3),
+        Score::Twos(6),
+        Score::Threes(9),
+        Score::Fours(12),
+        Score::Fives(15),
+        Score::Sixes(18),
+    ] {
+        upper_section.update_score(score);
+    }
+    group.bench_function("upper_score_bonus", |b| {
+        b.iter(|| black_box(Score::upper_score_bonus(black_box(&upper_section))))
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, possible_scores, evaluators);
+criterion_main!(benches);
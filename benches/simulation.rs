@@ -0,0 +1,19 @@
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use yahtzee::strategy::{self, Greedy};
+
+fn full_games(c: &mut Criterion) {
+    let mut group = c.benchmark_group("simulated games");
+    group.throughput(Throughput::Elements(1));
+
+    group.bench_function("greedy solo game", |b| {
+        let mut rng = StdRng::seed_from_u64(0);
+        b.iter(|| strategy::play_game("bench".to_owned(), &mut Greedy, &mut rng).score)
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, full_games);
+criterion_main!(benches);
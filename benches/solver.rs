@@ -0,0 +1,25 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use yahtzee::score::Category;
+use yahtzee::solver::{Card, Solver};
+
+fn solving(c: &mut Criterion) {
+    let mut group = c.benchmark_group("solver");
+    group.sample_size(10);
+
+    let solver = Solver::endgame(2);
+    let card = Card {
+        filled: 0x1fff & !(1 << Category::Chance.index()),
+        upper: 0,
+        yahtzee_bonus: false,
+    };
+    group.bench_function("turn", |b| b.iter(|| solver.turn(black_box(card)).card()));
+
+    group.bench_function("last two turns", |b| {
+        b.iter(|| Solver::endgame(black_box(2)))
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, solving);
+criterion_main!(benches);
@@ -1,91 +1,1924 @@
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Duration;
+use std::{env, fmt, io, process, str};
+use yahtzee::analysis::{self, AnalysisError, Review};
+use yahtzee::clock::{Fallback, SystemClock, TimeControl};
+use yahtzee::daily::{self, DailyResult, Date};
+use yahtzee::engine;
+use yahtzee::game::{Game, GameError, SAVE_FILE};
+use yahtzee::hand::DiceHand;
 use yahtzee::input::*;
+use yahtzee::leaderboard;
+use yahtzee::match_play::{Match, MatchScoring};
 use yahtzee::player::Player;
+use yahtzee::probability::{self, Target};
+use yahtzee::ratings::{self, PlayerRating};
+use yahtzee::rules::Rules;
+use yahtzee::score::{Category, Score};
+use yahtzee::solver::{Solved, Solver, Spread, SOLVER_FILE, SPREAD_FILE};
+use yahtzee::standings::{self, Standing, TieBreak};
+use yahtzee::stats::{self, PlayerStats};
+use yahtzee::storage;
+use yahtzee::strategy::{self, Bot, Strategy};
+use yahtzee::team::Team;
+use yahtzee::threshold::Threshold;
+use yahtzee::tournament::Tournament;
+use yahtzee::trainer::{self, Position, Session};
+use yahtzee::transcript::{Decision, Transcript, TRANSCRIPT_FILE};
+use yahtzee::turn_order::{self, RollOff, TurnOrder};
 
-const NUM_ROUNDS: u8 = 13;
+const USAGE: &str = "usage: yahtzee [options]
+       yahtzee scorekeeper [options]
+       yahtzee resume [file]
+       yahtzee analyze [transcript]
+       yahtzee train
+       yahtzee verify <result> [transcript]
+       yahtzee simulate <bot> <bot>... [--games <count>] [--seed <seed>]
+       yahtzee tournament [<bot>...] [--games <count>] [--seed <seed>] [--csv <file>]
+       yahtzee engine <bot>
+       yahtzee stats [name]
+       yahtzee ratings [name]
+       yahtzee scores
+
+options: --dice <count> --faces <count> --practice --daily
+         --tie-break <shared|yahtzees|upper> --target <score>
+         --teams <count> --shared-card --blitz --turn-time <seconds>
+         --game-time <seconds> --timeout-score <best|lowest>
+         --match <games> --match-scoring <total|wins>
+         --order <entered|shuffle|shuffle:<seed>|roll-off>
+         --bot <greedy|ev|win|target:<score>|engine:<command>>
+         --engine <command>";
+
+/// what the program was asked to do
+enum Mode {
+    Play(Options),
+    Scorekeeper(Options),
+    Resume(Option<String>),
+    Analyze(Option<String>),
+    Train,
+    /// a daily result and the transcript to check it against
+    Verify(String, Option<String>),
+    Simulate(Simulation),
+    Tournament(TournamentOptions),
+    /// play a bot through the engine protocol on standard input and output
+    Engine(Bot),
+    Stats(Option<String>),
+    /// the ratings leaderboard, or one player's rating history
+    Ratings(Option<String>),
+    /// the high score boards
+    Scores,
+}
+
+impl Mode {
+    fn parse<I: Iterator<Item = String>>(args: I) -> Result<Mode, String> {
+        let args: Vec<String> = args.collect();
+        match args.first().map(String::as_str) {
+            Some("stats") if args.len() <= 2 => Ok(Mode::Stats(args.get(1).cloned())),
+            Some("stats") => Err("stats takes at most one name".to_owned()),
+            Some("ratings") if args.len() <= 2 => Ok(Mode::Ratings(args.get(1).cloned())),
+            Some("ratings") => Err("ratings takes at most one name".to_owned()),
+            Some("scores") if args.len() == 1 => Ok(Mode::Scores),
+            Some("scores") => Err("scores takes no arguments".to_owned()),
+            Some("resume") if args.len() <= 2 => Ok(Mode::Resume(args.get(1).cloned())),
+            Some("resume") => Err("resume takes at most one file".to_owned()),
+            Some("analyze") if args.len() <= 2 => Ok(Mode::Analyze(args.get(1).cloned())),
+            Some("analyze") => Err("analyze takes at most one transcript".to_owned()),
+            Some("train") if args.len() == 1 => Ok(Mode::Train),
+            Some("train") => Err("train takes no arguments".to_owned()),
+            Some("verify") if (2..=3).contains(&args.len()) => {
+                Ok(Mode::Verify(args[1].clone(), args.get(2).cloned()))
+            }
+            Some("verify") => Err("verify takes a result and at most one transcript".to_owned()),
+            Some("simulate") => Simulation::parse(args.into_iter().skip(1)).map(Mode::Simulate),
+            Some("tournament") => {
+                TournamentOptions::parse(args.into_iter().skip(1)).map(Mode::Tournament)
+            }
+            Some("engine") if args.len() == 2 => args[1]
+                .parse()
+                .map(Mode::Engine)
+                .map_err(|error| format!("{}", error)),
+            Some("engine") => Err("engine takes one bot".to_owned()),
+            Some("scorekeeper") => match Options::parse(args.into_iter().skip(1))? {
+                options if options.daily => {
+                    Err("the daily challenge can't be played with real dice".to_owned())
+                }
+                options if !options.bots.is_empty() => {
+                    Err("bots can't play with real dice".to_owned())
+                }
+                options if options.time_control.is_some() => {
+                    Err("games with real dice can't be timed".to_owned())
+                }
+                options => Ok(Mode::Scorekeeper(Options {
+                    real_dice: true,
+                    ..options
+                })),
+            },
+            _ => Options::parse(args.into_iter()).map(Mode::Play),
+        }
+    }
+}
+
+/// options passed on the command line
+#[derive(Debug, Default)]
+struct Options {
+    rules: Rules,
+    /// allow taking back any number of moves, without recording stats
+    practice: bool,
+    tie_break: TieBreak,
+    /// play today's daily challenge
+    daily: bool,
+    /// computer players to seat after the people
+    bots: Vec<Bot>,
+    /// the final score hints aim for
+    target: Option<u32>,
+    /// how many teams to split the people into, or 0 to play alone
+    teams: usize,
+    /// each team plays one scorecard
+    shared_card: bool,
+    time_control: Option<TimeControl>,
+    /// the dice are rolled outside the game and entered by hand
+    real_dice: bool,
+    /// how many games to play, or None to keep asking for another
+    match_length: Option<usize>,
+    match_scoring: MatchScoring,
+    turn_order: TurnOrder,
+}
+
+impl Options {
+    fn parse<I: Iterator<Item = String>>(mut args: I) -> Result<Options, String> {
+        let defaults = Rules::default();
+        let mut num_dice = defaults.num_dice;
+        let mut num_faces = defaults.num_faces;
+        let mut practice = false;
+        let mut tie_break = TieBreak::default();
+        let mut daily = false;
+        let mut bots: Vec<Bot> = Vec::new();
+        let mut target = None;
+        let mut teams = 0;
+        let mut shared_card = false;
+        let mut blitz = false;
+        let mut per_turn: Option<u64> = None;
+        let mut per_game: Option<u64> = None;
+        let mut fallback: Option<Fallback> = None;
+        let mut match_length = None;
+        let mut match_scoring = MatchScoring::default();
+        let mut turn_order = TurnOrder::default();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--dice" => num_dice = flag_value(&arg, args.next())?,
+                "--faces" => num_faces = flag_value(&arg, args.next())?,
+                "--practice" => practice = true,
+                "--tie-break" => tie_break = flag_value(&arg, args.next())?,
+                "--daily" => daily = true,
+                "--bot" => bots.push(flag_value(&arg, args.next())?),
+                "--engine" => bots.push(Bot::Engine(flag_value(&arg, args.next())?)),
+                "--target" => target = Some(flag_value(&arg, args.next())?),
+                "--teams" => teams = flag_value(&arg, args.next())?,
+                "--shared-card" => shared_card = true,
+                "--blitz" => blitz = true,
+                "--turn-time" => per_turn = Some(flag_value(&arg, args.next())?),
+                "--game-time" => per_game = Some(flag_value(&arg, args.next())?),
+                "--timeout-score" => fallback = Some(flag_value(&arg, args.next())?),
+                "--match" => match_length = Some(flag_value(&arg, args.next())?),
+                "--match-scoring" => match_scoring = flag_value(&arg, args.next())?,
+                "--order" => turn_order = flag_value(&arg, args.next())?,
+                _ => return Err(format!("unknown argument {}", arg)),
+            }
+        }
+
+        let rules = Rules::new(num_dice, num_faces).map_err(|error| error.to_string())?;
+        // everyone's daily scores have to come from the same game to compare
+        if daily && !rules.is_standard() {
+            return Err("the daily challenge is always played with 5d6".to_owned());
+        }
+        if daily && practice {
+            return Err("the daily challenge can't be played as practice".to_owned());
+        }
+        // every player gets the same dice, so later players would know what's coming
+        if daily && !bots.is_empty() {
+            return Err("the daily challenge is played alone".to_owned());
+        }
+        if !rules.is_standard() && bots.iter().any(|bot| *bot != Bot::Greedy) {
+            return Err("only the greedy bot can play with other dice".to_owned());
+        }
+        if teams == 1 {
+            return Err("team games need at least two teams".to_owned());
+        }
+        if shared_card && teams == 0 {
+            return Err("--shared-card needs --teams".to_owned());
+        }
+        if teams > 0 && daily {
+            return Err("the daily challenge can't be played in teams".to_owned());
+        }
+        if teams > 0 && !bots.is_empty() {
+            return Err("bots can't play in team games".to_owned());
+        }
+        if match_length == Some(0) {
+            return Err("a match needs at least one game".to_owned());
+        }
+        if daily && match_length.is_some() {
+            return Err("the daily challenge is a single game".to_owned());
+        }
+        if per_turn == Some(0) || per_game == Some(0) {
+            return Err("players need more than no time".to_owned());
+        }
+        let time_control = match (blitz, per_turn, per_game) {
+            (false, None, None) if fallback.is_some() => {
+                return Err("--timeout-score needs a clock".to_owned())
+            }
+            (false, None, None) => None,
+            (blitz, per_turn, per_game) => {
+                let defaults = match blitz {
+                    true => TimeControl::BLITZ,
+                    false => TimeControl::default(),
+                };
+                Some(TimeControl {
+                    per_turn: per_turn.map(Duration::from_secs).or(defaults.per_turn),
+                    per_game: per_game.map(Duration::from_secs).or(defaults.per_game),
+                    fallback: fallback.unwrap_or(defaults.fallback),
+                })
+            }
+        };
+        Ok(Options {
+            rules,
+            practice,
+            tie_break,
+            daily,
+            bots,
+            target,
+            teams,
+            shared_card,
+            time_control,
+            real_dice: false,
+            match_length,
+            match_scoring,
+            turn_order,
+        })
+    }
+}
+
+/// bots to play against each other many times
+#[derive(Debug)]
+struct Simulation {
+    bots: Vec<Bot>,
+    games: u32,
+    seed: Option<u64>,
+}
+
+impl Simulation {
+    fn parse<I: Iterator<Item = String>>(mut args: I) -> Result<Simulation, String> {
+        let mut simulation = Simulation {
+            bots: Vec::new(),
+            games: 1000,
+            seed: None,
+        };
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--games" => simulation.games = flag_value(&arg, args.next())?,
+                "--seed" => simulation.seed = Some(flag_value(&arg, args.next())?),
+                "--engine" => simulation
+                    .bots
+                    .push(Bot::Engine(flag_value(&arg, args.next())?)),
+                bot => simulation
+                    .bots
+                    .push(bot.parse().map_err(|error| format!("{}", error))?),
+            }
+        }
+        if simulation.bots.len() < 2 {
+            return Err("simulate needs at least two bots".to_owned());
+        }
+        Ok(simulation)
+    }
+}
+
+/// bots to play against each other in every pairing
+#[derive(Debug)]
+struct TournamentOptions {
+    bots: Vec<Bot>,
+    /// games for each pairing
+    games: u32,
+    seed: Option<u64>,
+    /// where to write the standings as CSV
+    csv: Option<String>,
+}
+
+impl TournamentOptions {
+    fn parse<I: Iterator<Item = String>>(mut args: I) -> Result<TournamentOptions, String> {
+        let mut options = TournamentOptions {
+            bots: Vec::new(),
+            games: 1000,
+            seed: None,
+            csv: None,
+        };
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--games" => options.games = flag_value(&arg, args.next())?,
+                "--seed" => options.seed = Some(flag_value(&arg, args.next())?),
+                "--csv" => options.csv = Some(flag_value(&arg, args.next())?),
+                "--engine" => options
+                    .bots
+                    .push(Bot::Engine(flag_value(&arg, args.next())?)),
+                bot => options
+                    .bots
+                    .push(bot.parse().map_err(|error| format!("{}", error))?),
+            }
+        }
+        // every built in bot plays unless some are picked
+        if options.bots.is_empty() {
+            options.bots = Bot::ALL.to_vec();
+        }
+        if options.bots.len() < 2 {
+            return Err("a tournament needs at least two bots".to_owned());
+        }
+        if options.games < 2 {
+            return Err("a tournament needs at least two games a pairing".to_owned());
+        }
+        if !options.games.is_multiple_of(2) {
+            return Err(
+                "tournament games are played in pairs, so --games has to be even".to_owned(),
+            );
+        }
+        Ok(options)
+    }
+}
+
+/// parse the value following a flag
+fn flag_value<T>(flag: &str, value: Option<String>) -> Result<T, String>
+where
+    T: str::FromStr,
+    T::Err: fmt::Display,
+{
+    let value = value.ok_or(format!("{} needs a value", flag))?;
+    value
+        .parse()
+        .map_err(|error| format!("invalid value {} for {}: {}", value, flag, error))
+}
 
 fn introduction() {
     println!("Hello and welcome to YAHTZEE!!!");
 }
 
-fn display_round<T: std::fmt::Display>(
-    player: &Player,
-    roll_counter: u32,
-    possible_scores: &Vec<T>,
-) {
-    println!("{}'s Roll: {}", player.name, roll_counter);
+fn display_possible_scores(possible_scores: &[Score]) {
     println!("Possible Scores:");
-    for (i, possible_score) in possible_scores.iter().enumerate() {
-        println!("\tScore {}: {}", (i + 1), possible_score)
+    for possible_score in possible_scores {
+        if let Some(category) = possible_score.category() {
+            println!("\t[{}] {}", category.code(), possible_score)
+        }
     }
+}
+
+fn display_roll(game: &Game) {
+    let player = game.current_player();
+    println!("{}'s Roll: {}", player.name, game.rolls);
+    display_possible_scores(&player.possible_scores());
     println!("{}", player);
 }
 
-fn round(player: &mut Player) {
-    player.roll_dice();
-    'round: for roll_counter in 1..4 {
-        let possible_scores = player.possible_scores();
-        display_round(player, roll_counter, &possible_scores);
-        if roll_counter < 3 {
-            loop {
-                println!("Enter the dice you'd like to reroll (Enter nothing to score)");
-                match read_values::<u8>() {
-                    Some(result) => match result {
-                        Ok(dice) => {
-                            player.reroll(dice);
-                            continue 'round;
-                        }
-                        Err(error) => println!("error: {}", error),
-                    },
-                    None => break,
+fn display_help() {
+    println!("Commands:");
+    for (command, description) in COMMAND_HELP {
+        println!("\t{:<18} {}", command, description);
+    }
+    if completes() {
+        println!("Tab completes score names, except in timed games and any game after one");
+    } else {
+        println!("Tab completion is off in timed games and for the rest of the session after one");
+    }
+}
+
+/// tells the player Tab completes score names, if it does in this game
+fn tab_hint(game: &Game) -> &'static str {
+    if game.time_control.is_none() && completes() {
+        " (Tab completes)"
+    } else {
+        ""
+    }
+}
+
+fn display_card(player: &Player) {
+    println!("{}'s Scorecard:", player.name);
+    for category in Category::for_rules(&player.rules) {
+        let points = match player.scored(category) {
+            Some(score) => score.value().to_string(),
+            None => "-".to_owned(),
+        };
+        println!(
+            "\t[{:>2}] {:<16} {:>3}",
+            category.code(),
+            category.to_string(),
+            points
+        );
+    }
+    println!("\t{}", upper_status(player));
+    println!("\tTotal: {}", player.score);
+}
+
+/// a time left as minutes and seconds, rounded up so it only shows no time
+/// once there's none
+fn format_time(time: Duration) -> String {
+    let seconds = time.as_millis().div_ceil(1000);
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+/// scores for the current player if their time has run out, returning
+/// whether it had
+fn out_of_time(game: &mut Game, rng: &mut impl rand::Rng) -> bool {
+    let name = game.turn_taker().to_owned();
+    match game.check_clock(&SystemClock, rng) {
+        Ok(Some(score)) => {
+            println!("{} is out of time! {} was scored", name, score);
+            true
+        }
+        Ok(None) => false,
+        Err(error) => {
+            println!("error: {}", error);
+            false
+        }
+    }
+}
+
+/// every team's total so far, when playing in teams
+fn display_team_totals(game: &Game) {
+    if game.teams.is_empty() || game.shared_cards {
+        return;
+    }
+    let totals: Vec<String> = game
+        .teams
+        .iter()
+        .enumerate()
+        .map(|(team, Team { name, .. })| format!("{} {}", name, game.team_score(team)))
+        .collect();
+    println!("\tTeam totals: {}", totals.join(", "));
+}
+
+/// the upper section subtotal and how it's tracking against the bonus
+fn upper_status(player: &Player) -> String {
+    let total = player.upper_total();
+    if player.has_upper_bonus() {
+        return format!("Upper: {}, bonus earned", total);
+    }
+    let versus_par = i64::from(total) - i64::from(player.upper_par());
+    format!(
+        "Upper: {}/{}, {} needed for the bonus ({:+} vs par)",
+        total,
+        player.rules.upper_bonus_threshold(),
+        player.upper_bonus_needed(),
+        versus_par
+    )
+}
+
+fn valid_dice(player: &Player, dice: &[u8]) -> Result<(), GameError> {
+    match dice
+        .iter()
+        .find(|die| **die < 1 || **die as usize > player.dice.len())
+    {
+        Some(die) => Err(GameError::DieOutOfRange(*die)),
+        None => Ok(()),
+    }
+}
+
+fn display_probabilities(player: &Player, reroll: &[u8], rolls_remaining: u32) {
+    let held: Vec<bool> = (1..=player.dice.len() as u8)
+        .map(|die| !reroll.contains(&die))
+        .collect();
+
+    let mut targets = Target::standard();
+    let mut held_faces: Vec<u32> = player
+        .dice
+        .iter()
+        .zip(held.iter())
+        .filter(|(_, held)| **held)
+        .map(|(die, _)| *die)
+        .collect();
+    held_faces.sort_unstable();
+    held_faces.dedup();
+    for face in held_faces {
+        targets.push(Target::AtLeast { count: 3, face });
+    }
+
+    println!("Odds with {} roll(s) left:", rolls_remaining);
+    for target in targets {
+        let odds =
+            probability::probability(&player.rules, &player.dice, &held, rolls_remaining, target);
+        println!("\t{}: {:.2}%", target, odds * 100.0);
+    }
+}
+
+/// where a game is saved when no file is given
+fn save_path(path: Option<String>) -> io::Result<PathBuf> {
+    match path {
+        Some(path) => Ok(PathBuf::from(path)),
+        None => storage::data_file(SAVE_FILE),
+    }
+}
+
+fn save_game(game: &Game, path: Option<String>) {
+    match save_path(path).and_then(|path| game.save(&path).map(|_| path)) {
+        Ok(path) => println!("Game saved to {}", path.display()),
+        Err(error) => println!("Could not save the game: {}", error),
+    }
+}
+
+/// how a turn came to an end
+enum TurnEnd {
+    Scored,
+    /// a move was taken back or put back, which may change whose turn it is
+    Rewound,
+    Quit,
+}
+
+fn rewind(game: &mut Game, command: Command) -> Result<TurnEnd, GameError> {
+    match command {
+        Command::Undo => game.undo()?,
+        _ => game.redo()?,
+    }
+    println!(
+        "{}",
+        if let Command::Undo = command {
+            "Undone"
+        } else {
+            "Redone"
+        }
+    );
+    Ok(TurnEnd::Rewound)
+}
+
+/// scores a category for the current player, checking first when it's worth
+/// nothing, and returns whether it was scored
+fn score_category(game: &mut Game, category: Category) -> bool {
+    if let Some(score) = game.current_player().possible_score(category) {
+        if score.value() == 0 && !confirm(&format!("Score 0 points for {}?", category)) {
+            return false;
+        }
+    }
+
+    let name = game.current_player().name.clone();
+    match game.score(category) {
+        Ok(score) => {
+            println!("{} scored! {}", name, score);
+            true
+        }
+        Err(error) => {
+            println!("error: {}", error);
+            false
+        }
+    }
+}
+
+/// the open categories of the current player, for tab completion
+fn open_categories(game: &Game) -> Vec<Category> {
+    game.current_player()
+        .possible_scores()
+        .iter()
+        .filter_map(Score::category)
+        .collect()
+}
+
+fn turn(game: &mut Game) -> TurnEnd {
+    let mut rng = rand::thread_rng();
+    // the roll waits while the last score can still be taken back, unless
+    // practicing where rolling doesn't lock it in, and a resumed game
+    // carries on with the dice it was saved with
+    if game.rolls == 0 && (game.practice || !game.can_take_back()) {
+        game.roll(&mut rng)
+            .expect("a new turn can always be rolled");
+    }
+    if game.rolls > 0 {
+        display_roll(game);
+    }
+
+    let mut stopped = false;
+    loop {
+        // the clock waits for the roll, while the last score can be taken back
+        if game.rolls > 0 {
+            game.start_clock(&SystemClock);
+        }
+        let left = game.time_left(&SystemClock);
+        if let Some(left) = left {
+            print!("[{} left] ", format_time(left));
+        }
+        if game.rolls == 0 {
+            println!("Press enter to roll, or undo to take back the last move");
+        } else if game.rolls_left() > 0 && !stopped {
+            println!("Enter the dice you'd like to reroll, a score to take, or help");
+        } else {
+            println!("Select a score by its name or code{}", tab_hint(game));
+        }
+
+        let deadline = left.filter(|_| game.rolls > 0);
+        let command = match read_command_within(&open_categories(game), deadline) {
+            Ok(command) => command,
+            Err(CommandError::EndOfInput) => return TurnEnd::Quit,
+            Err(CommandError::OutOfTime) if out_of_time(game, &mut rng) => return TurnEnd::Scored,
+            Err(CommandError::OutOfTime) => continue,
+            Err(error) => {
+                println!("error: {}", error);
+                continue;
+            }
+        };
+        if out_of_time(game, &mut rng) {
+            return TurnEnd::Scored;
+        }
+
+        let num_dice = game.current_player().dice.len() as u8;
+        let result = match command {
+            Command::Help => {
+                display_help();
+                Ok(())
+            }
+            Command::Card => {
+                display_card(game.current_player());
+                display_team_totals(game);
+                Ok(())
+            }
+            Command::Reroll(dice) => game.reroll(dice, &mut rng).map(|()| display_roll(game)),
+            Command::Keep(values) => game.keep(&values, &mut rng).map(|()| display_roll(game)),
+            Command::Probability(_) if game.rolls_left() == 0 => Err(GameError::NoRollsLeft),
+            Command::Probability(dice) => valid_dice(game.current_player(), &dice)
+                .map(|()| display_probabilities(game.current_player(), &dice, game.rolls_left())),
+            Command::Stop | Command::Roll if game.rolls == 0 => {
+                game.roll(&mut rng).map(|()| display_roll(game))
+            }
+            Command::Roll => game
+                .reroll((1..=num_dice).collect(), &mut rng)
+                .map(|()| display_roll(game)),
+            Command::Stop => {
+                stopped = true;
+                Ok(())
+            }
+            Command::Score(category) => {
+                if score_category(game, category) {
+                    return TurnEnd::Scored;
                 }
+                Ok(())
+            }
+            Command::Undo | Command::Redo => match rewind(game, command) {
+                Ok(end) => return end,
+                Err(error) => Err(error),
+            },
+            Command::Hint => {
+                display_hint(game);
+                Ok(())
             }
+            Command::Save(path) => {
+                save_game(game, path);
+                Ok(())
+            }
+            Command::Quit => {
+                if confirm("Quit the game? Anything not saved will be lost") {
+                    return TurnEnd::Quit;
+                }
+                Ok(())
+            }
+        };
+
+        if let Err(error) = result {
+            println!("error: {}", error);
         }
+    }
+}
 
-        let score_index: usize = loop {
-            println!("Select a possible score");
-            match read_value() {
-                Ok(score_index) if score_index > possible_scores.len() => {
-                    println!("Your selection is too high")
+/// a turn played by a computer player, which first lets the last move be
+/// taken back if a person made it
+fn bot_turn(game: &mut Game, strategy: &mut dyn Strategy) -> TurnEnd {
+    let name = game.current_player().name.clone();
+    if game.rolls == 0 && game.can_take_back() {
+        loop {
+            println!(
+                "Press enter for {}'s turn, or undo to take back the last move",
+                name
+            );
+            let result = match read_command(&[]) {
+                Ok(Command::Stop) | Ok(Command::Roll) => break,
+                Ok(command @ Command::Undo) | Ok(command @ Command::Redo) => {
+                    match rewind(game, command) {
+                        Ok(end) => return end,
+                        Err(error) => Err(error.to_string()),
+                    }
                 }
-                Ok(score_index) if score_index < 1 => println!("Your selection is too low"),
-                Ok(score_index) => break score_index,
-                Err(err) => {
-                    println!("{}", err);
-                    continue;
+                Ok(Command::Save(path)) => {
+                    save_game(game, path);
+                    Ok(())
                 }
+                Ok(Command::Quit) | Err(CommandError::EndOfInput) => return TurnEnd::Quit,
+                Ok(_) => Err("the bot is about to play, press enter to let it".to_owned()),
+                Err(error) => Err(error.to_string()),
+            };
+            if let Err(error) = result {
+                println!("error: {}", error);
+            }
+        }
+    }
+
+    let mut rng = rand::thread_rng();
+    strategy.observe(&game.players, game.current);
+    let faults = strategy.faults().len();
+    if game.rolls == 0 {
+        game.roll(&mut rng)
+            .expect("a new turn can always be rolled");
+    }
+    game.start_clock(&SystemClock);
+    loop {
+        let dice: Vec<String> = game
+            .current_player()
+            .dice
+            .iter()
+            .map(u32::to_string)
+            .collect();
+        println!("{} rolled {}", name, dice.join(" "));
+        if game.rolls_left() == 0 {
+            break;
+        }
+        let reroll = strategy.reroll(game.current_player(), game.rolls_left());
+        if reroll.is_empty() {
+            break;
+        }
+        game.reroll(reroll, &mut rng)
+            .expect("bots reroll dice they have");
+    }
+
+    let player = game.current_player();
+    let score = strategy.score(player, &player.possible_scores());
+    let category = score.category().expect("bots pick a category");
+    match game.score(category) {
+        Ok(score) => println!("{} scored! {}", name, score),
+        Err(error) => panic!("{} picked a score it can't take: {}", name, error),
+    }
+    for fault in &strategy.faults()[faults..] {
+        println!("warning: {} made a safe move instead: {}", name, fault);
+    }
+    TurnEnd::Scored
+}
+
+/// a turn played with real dice, where the player enters what they rolled
+fn scorekeeper_turn(game: &mut Game) -> TurnEnd {
+    // a score that was taken back is picked again from the same dice
+    while game.rolls == 0 {
+        let player = game.current_player();
+        println!(
+            "Enter the {} dice {} rolled, separated by spaces",
+            player.rules.num_dice, player.name
+        );
+        if game.can_undo() {
+            println!("or undo to take back the last move");
+        }
+
+        let words = match get_dice::<String>() {
+            Ok(words) if words.is_empty() => {
+                println!("The dice are needed to score the round");
+                continue;
+            }
+            Ok(words) => words,
+            Err(CommandError::EndOfInput) => return TurnEnd::Quit,
+            Err(error) => {
+                println!("error: {}", error);
+                continue;
             }
         };
+        if let [word] = words.as_slice() {
+            if let Ok(command @ Command::Undo) | Ok(command @ Command::Redo) = word.parse() {
+                match rewind(game, command) {
+                    Ok(end) => return end,
+                    Err(error) => {
+                        println!("error: {}", error);
+                        continue;
+                    }
+                }
+            }
+        }
+
+        let dice: Result<Vec<u32>, _> = words.iter().map(|word| word.parse()).collect();
+        match dice {
+            Ok(dice) => match game.set_dice(dice) {
+                Ok(()) => break,
+                Err(error) => println!("error: {}", error),
+            },
+            Err(error) => println!("error: {}", error),
+        }
+    }
+
+    let player = game.current_player();
+    display_possible_scores(&player.possible_scores());
+    println!("{}", player);
+    loop {
+        println!("Select a score by its name or code{}", tab_hint(game));
+        match read_category(&open_categories(game)) {
+            Ok(Some(category)) => {
+                if score_category(game, category) {
+                    return TurnEnd::Scored;
+                }
+            }
+            Ok(None) => continue,
+            Err(CommandError::EndOfInput) => return TurnEnd::Quit,
+            Err(error) => println!("{}", error),
+        }
+    }
+}
+
+fn display_stats(name: &str, stats: &PlayerStats) {
+    println!(
+        "{}: {} game(s), average score {:.1}, high score {}",
+        name,
+        stats.games_played,
+        stats.average_score(),
+        stats.high_score
+    );
+    println!(
+        "\tUpper bonus in {:.1}% of games, {} Yahtzee(s)",
+        stats.bonus_rate() * 100.0,
+        stats.yahtzees
+    );
+    let zeroed: Vec<String> = stats
+        .most_zeroed()
+        .iter()
+        .take(3)
+        .map(|(category, count)| format!("{} ({})", category, count))
+        .collect();
+    if !zeroed.is_empty() {
+        println!("\tMost often zeroed: {}", zeroed.join(", "));
+    }
+}
+
+fn show_stats(name: Option<String>) {
+    let store = match stats::load() {
+        Ok(store) => store,
+        Err(error) => {
+            eprintln!("Could not read stats: {}", error);
+            process::exit(1);
+        }
+    };
 
-        let score = possible_scores[score_index - 1];
-        player.update_score(score);
-        println!("{} scored! {}", player.name, score);
-        break;
+    match name {
+        Some(name) => match store.get(&name) {
+            Some(player_stats) => display_stats(&name, player_stats),
+            None => println!("No games recorded for {}", name),
+        },
+        None if store.players.is_empty() => println!("No games recorded yet"),
+        None => {
+            for (name, player_stats) in store.players.iter() {
+                display_stats(name, player_stats);
+            }
+        }
+    }
+}
+
+fn display_rating_history(name: &str, rating: &PlayerRating) {
+    println!(
+        "{}: rated {:.0} after {} game(s)",
+        name, rating.rating, rating.games
+    );
+    for change in rating.history.iter() {
+        println!(
+            "\t{}  {} of {:<2} {:>5.0} ({:+.1})",
+            change.date, change.place, change.players, change.rating, change.change
+        );
+    }
+}
+
+fn show_ratings(name: Option<String>) {
+    let store = match ratings::load() {
+        Ok(store) => store,
+        Err(error) => {
+            eprintln!("Could not read ratings: {}", error);
+            process::exit(1);
+        }
+    };
+
+    match name {
+        Some(name) => match store.get(&name) {
+            Some(rating) => display_rating_history(&name, rating),
+            None => println!("No rated games recorded for {}", name),
+        },
+        None if store.players.is_empty() => println!("No rated games recorded yet"),
+        None => {
+            println!("Ratings:");
+            for (rank, (name, rating)) in store.leaderboard().iter().enumerate() {
+                let last = rating.history.last().map_or(0.0, |change| change.change);
+                println!(
+                    "\t{:<3} {:<16} {:>5.0}  {:>3} game(s)  last {:+.1}",
+                    format!("{}.", rank + 1),
+                    name,
+                    rating.rating,
+                    rating.games,
+                    last
+                );
+            }
+        }
+    }
+}
+
+fn show_scores() {
+    let leaderboard = match leaderboard::load() {
+        Ok(leaderboard) => leaderboard,
+        Err(error) => {
+            eprintln!("Could not read the leaderboard: {}", error);
+            process::exit(1);
+        }
+    };
+    if leaderboard.scores.is_empty() {
+        println!("No games recorded yet");
+        return;
+    }
+
+    for board in leaderboard.boards() {
+        println!("{}:", board);
+        for (rank, high_score) in leaderboard.board(board).iter().enumerate() {
+            let mut notes = Vec::new();
+            if let Some(seed) = high_score.seed {
+                notes.push(format!("seed {}", seed));
+            }
+            if high_score.bots {
+                notes.push("with bots".to_owned());
+            }
+            println!(
+                "\t{:<3} {:<16} {:>4}  {}  {}",
+                format!("{}.", rank + 1),
+                high_score.name,
+                high_score.score,
+                high_score.date,
+                notes.join(", ")
+            );
+        }
     }
 }
 
 fn main() {
+    let mode = match Mode::parse(env::args().skip(1)) {
+        Ok(mode) => mode,
+        Err(error) => {
+            eprintln!("{}\n{}", error, USAGE);
+            process::exit(2);
+        }
+    };
+
+    match mode {
+        Mode::Play(options) => play(options, turn),
+        Mode::Scorekeeper(options) => play(options, scorekeeper_turn),
+        Mode::Resume(path) => resume(path),
+        Mode::Analyze(path) => analyze(path),
+        Mode::Train => train(),
+        Mode::Verify(result, path) => verify(&result, path),
+        Mode::Simulate(simulation) => simulate(simulation),
+        Mode::Tournament(options) => tournament(options),
+        Mode::Engine(bot) => engine(bot),
+        Mode::Stats(name) => show_stats(name),
+        Mode::Ratings(name) => show_ratings(name),
+        Mode::Scores => show_scores(),
+    }
+}
+
+fn play(options: Options, turn: fn(&mut Game) -> TurnEnd) {
+    introduction();
+
+    if options.teams > 0 {
+        let teams = get_teams(options.teams);
+        let names: Vec<String> = teams.iter().map(|team| team.name.clone()).collect();
+        let order = pick_turn_order(options.turn_order, &names, &options.rules);
+        let teams = order.iter().map(|team| teams[*team].clone()).collect();
+        match Game::with_teams(options.rules, teams, options.shared_card) {
+            Ok(mut game) => {
+                game.practice = options.practice;
+                game.target = options.target;
+                game.tie_break = options.tie_break;
+                game.time_control = options.time_control;
+                game.real_dice = options.real_dice;
+                let series = new_match(&game, &options);
+                play_games(game, turn, series);
+            }
+            Err(error) => {
+                eprintln!("Could not start the game: {}", error);
+                process::exit(1);
+            }
+        }
+        return;
+    }
+
+    let mut names: Vec<String> = Vec::new();
+    while let Some(player_name) = get_player_name() {
+        names.push(player_name);
+        if options.daily {
+            break;
+        }
+        println!("Enter nothing when ready to continue.");
+    }
+    if names.is_empty() && options.bots.is_empty() {
+        return;
+    }
+
+    let mut bots = vec![None; names.len()];
+    for (seat, bot) in options.bots.iter().enumerate() {
+        names.push(bot_name(bot, seat + 1));
+        bots.push(Some(bot.clone()));
+    }
+    let order = pick_turn_order(options.turn_order, &names, &options.rules);
+    let names = order.iter().map(|seat| names[*seat].clone()).collect();
+    let bots = order.iter().map(|seat| bots[*seat].clone()).collect();
+    let mut game = Game::new(options.rules, names);
+    game.practice = options.practice;
+    game.bots = bots;
+    game.target = options.target;
+    game.tie_break = options.tie_break;
+    game.time_control = options.time_control;
+    game.real_dice = options.real_dice;
+    if options.daily {
+        let date = Date::today();
+        println!("Daily challenge for {}", date);
+        game.daily = Some(date);
+    }
+    let series = new_match(&game, &options);
+    play_games(game, turn, series);
+}
+
+/// a match between a game's players, as the options asked for
+fn new_match(game: &Game, options: &Options) -> Match {
+    let names = game
+        .players
+        .iter()
+        .map(|player| player.name.clone())
+        .collect();
+    Match::new(names, options.match_length, options.match_scoring)
+}
+
+/// Picks the order players take their turns in, by their index in `names`,
+/// showing how it was picked and the order.
+fn pick_turn_order(turn_order: TurnOrder, names: &[String], rules: &Rules) -> Vec<usize> {
+    let order = match turn_order {
+        TurnOrder::Entered => (0..names.len()).collect(),
+        TurnOrder::Shuffled(seed) => {
+            let seed = seed.unwrap_or_else(rand::random);
+            println!("Shuffling the turn order with seed {}", seed);
+            turn_order::shuffled(names.len(), seed)
+        }
+        TurnOrder::RollOff => {
+            let roll_off = RollOff::roll(names.len(), rules, &mut rand::thread_rng());
+            for (i, round) in roll_off.rounds.iter().enumerate() {
+                match i {
+                    0 => println!("Rolling to see who goes first:"),
+                    _ => println!("A tie for the highest, rolling again:"),
+                }
+                for (player, dice) in round.iter() {
+                    let total: u32 = dice.iter().sum();
+                    let dice: Vec<String> = dice.iter().map(u32::to_string).collect();
+                    println!("\t{:<16} {}  ({})", names[*player], dice.join(" "), total);
+                }
+            }
+            println!("{} goes first", names[roll_off.starter()]);
+            roll_off.order(names.len())
+        }
+    };
+    if order.len() > 1 {
+        let names: Vec<String> = order
+            .iter()
+            .enumerate()
+            .map(|(turn, player)| format!("{}. {}", turn + 1, names[*player]))
+            .collect();
+        println!("Turn order: {}", names.join(", "));
+    }
+    order
+}
+
+/// asks for the name and players of each team
+fn get_teams(count: usize) -> Vec<Team> {
+    (1..=count)
+        .map(|number| {
+            let name = get_name(&format!("What is team {} called?", number))
+                .unwrap_or_else(|| format!("Team {}", number));
+            println!(
+                "Who plays for {}? Enter nothing when ready to continue.",
+                name
+            );
+            let mut members = Vec::new();
+            while let Some(member) = get_player_name() {
+                members.push(member);
+            }
+            Team { name, members }
+        })
+        .collect()
+}
+
+/// carries on with a saved game
+fn resume(path: Option<String>) {
+    let game = save_path(path).and_then(|path| Game::load(&path));
+    match game {
+        Ok(game) => {
+            // games saved before matches were kept are a match of their own
+            let series = game.series.clone().unwrap_or_else(|| {
+                let names = game
+                    .players
+                    .iter()
+                    .map(|player| player.name.clone())
+                    .collect();
+                Match::new(names, None, MatchScoring::default())
+            });
+            play_games(game, turn, series)
+        }
+        Err(error) => {
+            eprintln!("Could not load the game: {}", error);
+            process::exit(1);
+        }
+    }
+}
+
+/// plays the games of a match, which if it has no length goes on for as
+/// long as the players would like another game
+fn play_games(mut game: Game, turn: fn(&mut Game) -> TurnEnd, mut series: Match) {
+    let first = game.clone();
+    let tie_break = game.tie_break;
     loop {
-        introduction();
+        // saved with the game, so a match can be carried on after a resume
+        game.series = Some(series.clone());
+        if let Some(length) = series.length {
+            println!("\nGame {} of {}", series.games.len() + 1, length);
+        }
+        if !play_game(&mut game, turn) {
+            return;
+        }
+        let players = standings::standings(&game.players, tie_break);
+        display_standings("Final Standings", &players, tie_break);
+        if !game.teams.is_empty() && !game.shared_cards {
+            display_standings("Team Standings", &game.team_standings(tie_break), tie_break);
+        }
+        record_ratings(&game, tie_break);
 
-        let mut players: Vec<Player> = Vec::new();
-        while let Some(player_name) = get_player_name() {
-            players.push(Player::new(player_name));
-            println!("Enter nothing when ready to continue.");
+        series.record(&game.players, tie_break);
+        if series.length.is_some() || series.games.len() > 1 {
+            display_match(&series);
+        }
+        if series.is_over() {
+            return;
+        }
+        if series.length.is_none() && !confirm("Play again with the same players?") {
+            return;
         }
-        for round_incr in 1..=NUM_ROUNDS {
-            for player in players.iter_mut() {
-                print!("\n{}'s Round {}", player.name, round_incr);
-                print!("  |  Current Score: {}\n", player.score);
-                round(player);
+        game = next_game(&first, &series);
+    }
+}
+
+/// a new game set up like an earlier one of the match, with its players
+/// seated for the match's next game
+fn next_game(earlier: &Game, series: &Match) -> Game {
+    // seats move along by one a game, so each player's seat in the earlier
+    // game comes from how many games were played before it
+    let count = series.players.len();
+    let before = earlier
+        .series
+        .as_ref()
+        .map_or(0, |series| series.games.len());
+    let moves = series.games.len() - before;
+    let seat = |player: &usize| (player + count - before % count) % count;
+    let seating = series.seating();
+    let names = seating
+        .iter()
+        .map(|player| earlier.players[seat(player)].name.clone())
+        .collect();
+    let mut game = Game::new(earlier.rules, names);
+    game.practice = earlier.practice;
+    game.bots = seating
+        .iter()
+        .map(|player| earlier.bot(seat(player)).cloned())
+        .collect();
+    game.target = earlier.target;
+    game.tie_break = earlier.tie_break;
+    // the teams they play for move along with them
+    game.teams = earlier.teams.clone();
+    if !game.teams.is_empty() {
+        let turns = moves % game.teams.len();
+        game.teams.rotate_left(turns);
+    }
+    game.shared_cards = earlier.shared_cards;
+    game.time_control = earlier.time_control;
+    game.real_dice = earlier.real_dice;
+    game
+}
+
+fn display_match(series: &Match) {
+    let totals = series.totals();
+    let wins = series.wins();
+    println!("\nMatch Standings ({}):", series.scoring);
+    for standing in series.standings() {
+        let player = series
+            .players
+            .iter()
+            .position(|name| *name == standing.name)
+            .unwrap_or_default();
+        println!(
+            "\t{:<3} {:<16} {:>2} win(s) {:>6} points",
+            format!("{}.", standing.place),
+            standing.name,
+            wins[player],
+            totals[player]
+        );
+    }
+
+    if !series.is_over() {
+        return;
+    }
+    let standings = series.standings();
+    let winners: Vec<&str> = standings
+        .iter()
+        .filter(|standing| standing.place == 1)
+        .map(|standing| standing.name.as_str())
+        .collect();
+    match winners.as_slice() {
+        [winner] => println!("{} wins the match!", winner),
+        winners => println!("The match is tied between {}!", winners.join(" and ")),
+    }
+}
+
+/// plays turns until the game is over, returning false if it was quit early
+fn play_game(game: &mut Game, turn: fn(&mut Game) -> TurnEnd) -> bool {
+    let solved = match game.bots.iter().flatten().any(|bot| bot.needs_solved()) {
+        true => Some(solved()),
+        false => None,
+    };
+    let mut strategies: Vec<Option<Box<dyn Strategy>>> = game
+        .bots
+        .iter()
+        .map(|bot| bot.as_ref().map(|bot| start_bot(bot, solved)))
+        .collect();
+
+    while !game.is_over() {
+        let player = game.current_player();
+        print!("\n{}'s Round {}", player.name, game.round);
+        if game.shared_cards {
+            print!(", {} to play", game.turn_taker());
+        }
+        print!("  |  Current Score: {}", player.score);
+        if let (Some(team), false) = (game.team_of(game.current), game.shared_cards) {
+            print!("  |  {}: {}", game.teams[team].name, game.team_score(team));
+        }
+        print!("  |  {}", upper_status(player));
+        match game.time_left(&SystemClock) {
+            Some(left) => println!("  |  Time: {}", format_time(left)),
+            None => println!(),
+        }
+        let end = match strategies.get_mut(game.current) {
+            Some(Some(strategy)) => bot_turn(game, strategy.as_mut()),
+            _ => turn(game),
+        };
+        match end {
+            TurnEnd::Quit => return false,
+            // the next player's clock starts when they roll
+            TurnEnd::Scored => game.stop_clock(&SystemClock),
+            TurnEnd::Rewound => {}
+        }
+    }
+
+    game.finish();
+    for player in game.players.iter() {
+        println!(
+            "Thank you for playing yahtzee {}. Your score was: {}",
+            player.name, player.score
+        );
+    }
+
+    // only regular games can be analyzed
+    if game.rules.is_standard() {
+        match storage::data_file(TRANSCRIPT_FILE)
+            .and_then(|path| game.transcript().save(&path).map(|_| path))
+        {
+            Ok(path) => println!(
+                "The game was saved to {}, run yahtzee analyze to see where points were lost",
+                path.display()
+            ),
+            Err(error) => println!("Could not save the transcript: {}", error),
+        }
+    }
+    if let Some(date) = game.daily {
+        display_daily_results(game, date);
+    }
+
+    // a shared scorecard isn't any one person's
+    if !game.shared_cards {
+        let people = people(game);
+        if let Err(error) = stats::record_game(&people) {
+            println!("Could not save stats: {}", error);
+        }
+        // dice entered by hand could be anything, so only games that rolled
+        // their own dice make the high scores
+        if !game.real_dice {
+            let date = game.daily.unwrap_or_else(Date::today);
+            match leaderboard::record_game(game, &people, date) {
+                Ok(places) => {
+                    for (name, place) in places {
+                        println!("{} is number {} on the high score board!", name, place);
+                    }
+                }
+                Err(error) => println!("Could not save the high scores: {}", error),
+            }
+        }
+    }
+    true
+}
+
+/// the players in a finished game whose results are kept between games:
+/// everyone but the bots, and nobody in practice games, which can be
+/// replayed move by move
+fn people(game: &Game) -> Vec<Player> {
+    if game.practice {
+        return Vec::new();
+    }
+    (0..game.players.len())
+        .filter(|player| game.bot(*player).is_none())
+        .map(|player| game.players[player].clone())
+        .collect()
+}
+
+/// updates the ratings of the people in a finished game from where they
+/// placed among each other
+fn record_ratings(game: &Game, tie_break: TieBreak) {
+    // team games aren't won by one person
+    if !game.teams.is_empty() {
+        return;
+    }
+    let standings = standings::standings(&people(game), tie_break);
+    match ratings::record_game(&standings, Date::today()) {
+        Ok(changes) if !changes.is_empty() => {
+            println!("\nRatings:");
+            for (standing, change) in standings.iter().zip(changes.iter()) {
+                println!(
+                    "\t{:<16} {:>5.0} ({:+.1})",
+                    standing.name, change.rating, change.change
+                );
             }
         }
+        Ok(_) => {}
+        Err(error) => println!("Could not save ratings: {}", error),
+    }
+}
+
+fn display_standings(title: &str, standings: &[Standing], tie_break: TieBreak) {
+    if standings.len() < 2 {
+        return;
+    }
+
+    println!("\n{}:", title);
+    for (i, standing) in standings.iter().enumerate() {
+        let shared = standings
+            .iter()
+            .enumerate()
+            .any(|(j, other)| j != i && other.place == standing.place);
+        let place = format!("{}{}", standing.place, if shared { "=" } else { "." });
+        match standing.behind {
+            0 => println!("\t{:<3} {:<16} {:>4}", place, standing.name, standing.score),
+            behind => println!(
+                "\t{:<3} {:<16} {:>4}  ({} behind)",
+                place, standing.name, standing.score, behind
+            ),
+        }
+    }
 
-        for player in players.iter_mut() {
-            player.endgame();
+    let winners: Vec<&str> = standings
+        .iter()
+        .filter(|standing| standing.place == 1)
+        .map(|standing| standing.name.as_str())
+        .collect();
+    match standings::margin(standings) {
+        Some(0) => println!("{} wins on {}!", winners[0], tie_break),
+        Some(margin) => println!("{} wins by {} points!", winners[0], margin),
+        None => println!(
+            "It's a tie between {} on {} points!",
+            winners.join(" and "),
+            standings[0].score
+        ),
+    }
+}
+
+/// the solver for optimal play, working it out the first time it's needed
+fn load_solver() -> Solver {
+    let path = match storage::data_file(SOLVER_FILE) {
+        Ok(path) => path,
+        Err(error) => {
+            eprintln!("Could not find the data directory: {}", error);
+            process::exit(1);
+        }
+    };
+    if !path.exists() {
+        println!("Working out optimal play, this only happens the first time...");
+    }
+    match Solver::load_or_new(&path) {
+        Ok(solver) => solver,
+        Err(error) => {
+            eprintln!("Could not save the solver: {}", error);
+            process::exit(1);
+        }
+    }
+}
+
+/// the solver and the spread of scores the optimal bots play with
+fn load_solved() -> Solved {
+    let solver = load_solver();
+    let path = match storage::data_file(SPREAD_FILE) {
+        Ok(path) => path,
+        Err(error) => {
+            eprintln!("Could not find the data directory: {}", error);
+            process::exit(1);
+        }
+    };
+    if !path.exists() {
+        println!("Working out how scores spread, this only happens the first time...");
+    }
+    match Spread::load_or_new(&path, &solver) {
+        Ok(spread) => Solved { solver, spread },
+        Err(error) => {
+            eprintln!("Could not save the spread of scores: {}", error);
+            process::exit(1);
+        }
+    }
+}
+
+/// the solver and spread of scores, loaded the first time they're needed
+fn solved() -> &'static Solved {
+    static SOLVED: OnceLock<Solved> = OnceLock::new();
+    SOLVED.get_or_init(load_solved)
+}
+
+/// shows the move that scores the most points on average, and the one most
+/// likely to reach the target if there is one
+fn display_hint(game: &Game) {
+    if game.daily.is_some() {
+        println!("Hints are off for the daily challenge");
+        return;
+    }
+    if !game.rules.is_standard() {
+        println!("Hints are only given for games of 5d6");
+        return;
+    }
+    let solved = solved();
+    let player = game.current_player();
+    let card = solved
+        .solver
+        .card(player)
+        .expect("the rules are checked above");
+    let mut threshold = game.target.map(|target| Threshold::new(solved, target));
+
+    if game.rolls == 0 {
+        let expected = f64::from(player.score) + solved.solver.value(card);
+        println!("Expected final score: {:.1}", expected);
+        if let Some(threshold) = threshold.as_mut() {
+            println!(
+                "Chance of reaching {}: {:.1}%",
+                threshold.target(),
+                threshold.chance(card, player.score) * 100.0
+            );
+        }
+        return;
+    }
+
+    let hand = DiceHand::from_dice(&player.dice).expect("the rules are checked above");
+    let rolls_left = game.rolls_left();
+    let turn = solved.solver.turn(card);
+    if let Some((decision, value)) = analysis::best_decision(&turn, hand, rolls_left) {
+        println!(
+            "Best for points: {} (expected final score {:.1})",
+            decision,
+            f64::from(player.score) + value
+        );
+    }
+    if let Some(threshold) = threshold.as_mut() {
+        let chances = threshold.turn(card, player.score);
+        if let Some((decision, chance)) = chances.best_decision(hand, rolls_left) {
+            println!(
+                "Best to reach {}: {} ({:.1}% chance)",
+                threshold.target(),
+                decision,
+                chance * 100.0
+            );
+        }
+    }
+}
+
+fn display_review(review: &Review, names: &[String]) {
+    let played = &review.played;
+    let dice: Vec<String> = played.dice.iter().map(u32::to_string).collect();
+    print!(
+        "\t{:>2} {:<12} [{}] {} roll(s) left: {} ({:.2})",
+        played.round,
+        names[played.player],
+        dice.join(" "),
+        played.rolls_left,
+        played.decision,
+        review.value
+    );
+    if review.regret() > 0.0 {
+        print!(", best was {} ({:.2})", review.best, review.best_value);
+    }
+    if review.is_blunder() {
+        print!("  <- blunder");
+    }
+    println!();
+}
+
+/// compares every decision in a finished game with optimal play
+fn analyze(path: Option<String>) {
+    let path = match path {
+        Some(path) => Ok(PathBuf::from(path)),
+        None => storage::data_file(TRANSCRIPT_FILE),
+    };
+    let transcript = match path.and_then(|path| Transcript::load(&path)) {
+        Ok(transcript) => transcript,
+        Err(error) => {
+            eprintln!("Could not load the transcript: {}", error);
+            process::exit(1);
+        }
+    };
+
+    let solver = load_solver();
+    let reviews = match analysis::analyze(&transcript, &solver) {
+        Ok(reviews) => reviews,
+        Err(error) => {
+            eprintln!("{}", error);
+            process::exit(1);
+        }
+    };
+
+    println!("Every decision, with the expected points still to come after it:");
+    for review in reviews.iter() {
+        display_review(review, &transcript.players);
+    }
+
+    println!("\nRegret:");
+    for (player, name) in transcript.players.iter().enumerate() {
+        let decisions = reviews
+            .iter()
+            .filter(|review| review.played.player == player)
+            .count();
+        println!(
+            "\t{} lost {:.2} expected points over {} decisions",
+            name,
+            analysis::total_regret(&reviews, player),
+            decisions
+        );
+    }
+
+    let blunders = analysis::blunders(&reviews, 5);
+    if blunders.is_empty() {
+        println!("\nNo blunders!");
+    } else {
+        println!("\nBiggest blunders:");
+        for review in blunders {
+            display_review(review, &transcript.players);
+        }
+    }
+}
+
+/// the decision a command stands for in a training position
+fn training_decision(position: &Position, command: Command) -> Result<Decision, String> {
+    let dice = &position.player.dice;
+    match command {
+        Command::Reroll(rerolled) => {
+            valid_dice(&position.player, &rerolled).map_err(|error| error.to_string())?;
+            let mut held: Vec<u32> = (1..=dice.len() as u8)
+                .filter(|die| !rerolled.contains(die))
+                .map(|die| dice[die as usize - 1])
+                .collect();
+            held.sort_unstable();
+            Ok(Decision::Hold(held))
+        }
+        Command::Keep(mut held) => {
+            held.sort_unstable();
+            Ok(Decision::Hold(held))
+        }
+        Command::Roll => Ok(Decision::Hold(vec![])),
+        Command::Score(category) => Ok(Decision::Score(category)),
+        _ => Err("enter the dice to reroll, keep <values> or a category to score".to_owned()),
+    }
+}
+
+fn display_session(session: &Session) {
+    println!(
+        "{}/{} best answers ({:.0}%), {:.2} expected points given up",
+        session.correct,
+        session.answered,
+        session.accuracy() * 100.0,
+        session.regret
+    );
+}
+
+/// quizzes the player on random positions, grading each answer against
+/// optimal play
+fn train() {
+    let solver = load_solver();
+    let mut rng = rand::thread_rng();
+    let mut session = Session::default();
+
+    println!("Pick the best move for each position, or quit to stop");
+    'positions: loop {
+        let position = Position::random(&mut rng);
+        println!();
+        display_card(&position.player);
+        let dice: Vec<String> = position.player.dice.iter().map(u32::to_string).collect();
+        println!(
+            "Round {}, {} roll(s) left, dice: {}",
+            position.round,
+            position.rolls_left,
+            dice.join(" ")
+        );
+
+        let review = loop {
+            let command = match read_command(&Category::STANDARD) {
+                Ok(Command::Quit) | Err(CommandError::EndOfInput) => break 'positions,
+                Ok(Command::Help) => {
+                    display_help();
+                    continue;
+                }
+                Ok(command) => command,
+                Err(error) => {
+                    println!("error: {}", error);
+                    continue;
+                }
+            };
+            let graded = training_decision(&position, command).and_then(|decision| {
+                position
+                    .grade(&solver, decision)
+                    .map_err(|error| match error {
+                        AnalysisError::InvalidMove { reason, .. } => reason.to_owned(),
+                        error => error.to_string(),
+                    })
+            });
+            match graded {
+                Ok(review) => break review,
+                Err(error) => println!("error: {}", error),
+            }
+        };
+
+        session.record(&review);
+        if trainer::is_correct(&review) {
+            println!("Correct! {} ({:.2})", review.best, review.best_value);
+        } else {
+            println!(
+                "The best move was {} ({:.2}), yours was worth {:.2}, {:.2} points less",
+                review.best,
+                review.best_value,
+                review.value,
+                review.regret()
+            );
+        }
+        display_session(&session);
+    }
+
+    println!();
+    display_session(&session);
+}
+
+/// prints everyone's result for the daily challenge, saving the transcript
+/// needed to check them
+fn display_daily_results(game: &Game, date: Date) {
+    let saved = storage::data_file(&daily::transcript_file(date))
+        .and_then(|path| game.transcript().save(&path).map(|_| path));
+    println!("\nDaily challenge results to share:");
+    for (index, player) in game.players.iter().enumerate() {
+        println!("\t{}: {}", player.name, DailyResult::new(date, game, index));
+    }
+    match saved {
+        Ok(path) => println!(
+            "Share {} too, so results can be checked with yahtzee verify \"<result>\" <transcript>",
+            path.display()
+        ),
+        Err(error) => println!("Could not save the transcript: {}", error),
+    }
+}
+
+/// checks a daily challenge result against the game it came from
+fn verify(result: &str, path: Option<String>) {
+    let result: DailyResult = match result.parse() {
+        Ok(result) => result,
+        Err(error) => {
+            eprintln!("{}", error);
+            process::exit(2);
+        }
+    };
+    let path = match path {
+        Some(path) => Ok(PathBuf::from(path)),
+        None => storage::data_file(&daily::transcript_file(result.date)),
+    };
+    let transcript = match path.and_then(|path| Transcript::load(&path)) {
+        Ok(transcript) => transcript,
+        Err(error) => {
+            eprintln!("Could not load the transcript: {}", error);
+            process::exit(1);
+        }
+    };
+
+    match daily::verify(&result, &transcript) {
+        Ok(name) => println!(
+            "Verified: {} scored {} in the daily challenge for {}",
+            name, result.score, result.date
+        ),
+        Err(error) => {
+            eprintln!("Not verified: {}", error);
+            process::exit(1);
+        }
+    }
+}
+
+/// plays bots against each other, taking turns to go first, and compares
+/// how often each wins
+fn simulate(simulation: Simulation) {
+    let solved = match simulation.bots.iter().any(|bot| bot.needs_solved()) {
+        true => Some(solved()),
+        false => None,
+    };
+    let mut rng = match simulation.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+
+    let seats = simulation.bots.len();
+    let mut wins = vec![0.0; seats];
+    let mut points = vec![0u64; seats];
+    let mut faults = vec![0; seats];
+    for game in 0..simulation.games as usize {
+        // seat the bots in turn so no one always goes first
+        let order: Vec<usize> = (0..seats).map(|seat| (seat + game) % seats).collect();
+        let mut strategies: Vec<Box<dyn Strategy>> = order
+            .iter()
+            .map(|bot| start_bot(&simulation.bots[*bot], solved))
+            .collect();
+        let players = strategy::play_match(&mut strategies, &mut rng);
+        for (strategy, bot) in strategies.iter().zip(order.iter()) {
+            faults[*bot] += strategy.faults().len();
+        }
+
+        let best = players.iter().map(|player| player.score).max().unwrap_or(0);
+        let winners = players.iter().filter(|player| player.score == best).count();
+        for (player, bot) in players.iter().zip(order) {
+            points[bot] += u64::from(player.score);
+            if player.score == best {
+                wins[bot] += 1.0 / winners as f64;
+            }
+        }
+    }
+
+    let games = f64::from(simulation.games.max(1));
+    println!(
+        "{} games, ties shared between the winners:",
+        simulation.games
+    );
+    let labels: Vec<String> = simulation
+        .bots
+        .iter()
+        .enumerate()
+        .map(|(seat, bot)| format!("{}. {}", seat + 1, bot))
+        .collect();
+    let width = labels.iter().map(String::len).max().unwrap_or(0).max(12);
+    println!(
+        "\t{:<width$} {:>8} {:>9} {:>14}",
+        "bot",
+        "wins",
+        "win rate",
+        "average score",
+        width = width
+    );
+    for (seat, label) in labels.iter().enumerate() {
+        println!(
+            "\t{:<width$} {:>8.1} {:>8.1}% {:>14.2}",
+            label,
+            wins[seat],
+            wins[seat] / games * 100.0,
+            points[seat] as f64 / games,
+            width = width
+        );
+    }
+    for (seat, bot) in simulation.bots.iter().enumerate() {
+        if faults[seat] > 0 {
             println!(
-                "Thank you for playing yahtzee {}. Your score was: {}",
-                player.name, player.score
+                "{}. {} made {} faults, which were played as safe moves",
+                seat + 1,
+                bot,
+                faults[seat]
             );
         }
     }
 }
+
+/// plays every bot against every other from the same seeds and ranks them,
+/// showing how sure the comparisons are
+fn tournament(options: TournamentOptions) {
+    let solved = match options.bots.iter().any(|bot| bot.needs_solved()) {
+        true => Some(solved()),
+        false => None,
+    };
+    let seed = options.seed.unwrap_or_else(rand::random);
+    let pairings = options.bots.len() * (options.bots.len() - 1) / 2;
+    println!(
+        "Playing {} games in each of {} pairings, seed {}...",
+        options.games, pairings, seed
+    );
+    let tournament = match Tournament::play(options.bots, options.games, seed, solved) {
+        Ok(tournament) => tournament,
+        Err(error) => {
+            eprintln!("Could not start an engine: {}", error);
+            process::exit(1);
+        }
+    };
+
+    let standings = tournament.standings();
+    let labels: Vec<String> = tournament.bots.iter().map(Bot::to_string).collect();
+    let width = labels.iter().map(String::len).max().unwrap_or(0).max(8);
+    println!(
+        "\n\t{:<4} {:<width$} {:>16} {:>8} {:>6} {:>5} {:>6} {:>5}",
+        "rank",
+        "bot",
+        "win rate",
+        "average",
+        "sd",
+        "p10",
+        "median",
+        "p90",
+        width = width
+    );
+    for (rank, standing) in standings.iter().enumerate() {
+        let scores = &standing.scores;
+        println!(
+            "\t{:<4} {:<width$} {:>16} {:>8.1} {:>6.1} {:>5} {:>6} {:>5}",
+            rank + 1,
+            labels[standing.bot],
+            format!(
+                "{:.1}% ± {:.1}%",
+                standing.win_rate.mean * 100.0,
+                standing.win_rate.margin * 100.0
+            ),
+            scores.mean,
+            scores.standard_deviation,
+            scores.p10,
+            scores.median,
+            scores.p90,
+            width = width
+        );
+    }
+
+    println!("\nHead to head, with 95% confidence intervals:");
+    for pairing in tournament.pairings.iter() {
+        let (first, second) = (&labels[pairing.first], &labels[pairing.second]);
+        let win_rate = pairing.win_rate();
+        let difference = pairing.score_difference();
+        let p_value = win_rate.p_value(0.5);
+        let significance = match p_value {
+            p if p < 0.001 => "p < 0.001".to_owned(),
+            p => format!("p = {:.3}", p),
+        };
+        println!(
+            "\t{} vs {}: {} won {:.1}% ± {:.1}% ({}), scoring {:+.1} ± {:.1} points a game",
+            first,
+            second,
+            first,
+            win_rate.mean * 100.0,
+            win_rate.margin * 100.0,
+            significance,
+            difference.mean,
+            difference.margin
+        );
+    }
+
+    if let Some(path) = options.csv {
+        match File::create(&path).and_then(|file| tournament.write_csv(file)) {
+            Ok(()) => println!("\nThe standings were saved to {}", path),
+            Err(error) => println!("\nCould not save the standings: {}", error),
+        }
+    }
+}
+
+/// the strategy for a bot, leaving if an engine can't be started
+fn start_bot<'a>(bot: &Bot, solved: Option<&'a Solved>) -> Box<dyn Strategy + 'a> {
+    match bot.strategy(solved) {
+        Ok(strategy) => strategy,
+        Err(error) => {
+            eprintln!("Could not start {}: {}", bot, error);
+            process::exit(1);
+        }
+    }
+}
+
+/// the name a computer player is seated under
+fn bot_name(bot: &Bot, seat: usize) -> String {
+    match bot {
+        Bot::Engine(command) => {
+            let program = command.split_whitespace().next().unwrap_or("engine");
+            let name = Path::new(program)
+                .file_name()
+                .map_or(program.into(), |name| name.to_string_lossy());
+            format!("{} engine {}", name, seat)
+        }
+        bot => format!("{} bot {}", bot, seat),
+    }
+}
+
+/// plays a bot as an engine for another program
+fn engine(bot: Bot) {
+    let solved = match bot.needs_solved() {
+        true => Some(solved()),
+        false => None,
+    };
+    let mut strategy = start_bot(&bot, solved);
+    let stdin = io::stdin();
+    if let Err(error) = engine::serve(strategy.as_mut(), stdin.lock(), io::stdout()) {
+        eprintln!("The engine stopped: {}", error);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,241 @@
+use crate::hand::{DiceHand, Keep};
+use crate::player::Player;
+use crate::rules::Rules;
+use crate::solver::{Solver, Turn};
+use crate::transcript::{Decision, Move, Transcript};
+use std::fmt;
+
+/// points of regret at which a decision counts as a blunder
+pub const BLUNDER: f64 = 3.0;
+
+/// tolerance for treating two expected values as the same
+const EPSILON: f64 = 1e-6;
+
+/// How one decision compares with the best one available
+#[derive(Debug, PartialEq, Clone)]
+pub struct Review {
+    /// position of the move in the transcript
+    pub index: usize,
+    pub played: Move,
+    /// the expected points still to come after the decision made
+    pub value: f64,
+    pub best: Decision,
+    /// the expected points still to come after the best decision
+    pub best_value: f64,
+}
+
+impl Review {
+    /// the expected points given up by the decision
+    ///
+    /// # Example
+    /// ```rust
+    /// use yahtzee::analysis::Review;
+    /// use yahtzee::score::Category;
+    /// use yahtzee::transcript::{Decision, Move};
+    ///
+    /// let review = Review {
+    ///     index: 0,
+    ///     played: Move {
+    ///         player: 0,
+    ///         round: 1,
+    ///         dice: vec![1, 1, 1, 1, 1],
+    ///         rolls_left: 0,
+    ///         decision: Decision::Score(Category::Aces),
+    ///     },
+    ///     value: 210.0,
+    ///     best: Decision::Score(Category::Yahtzee),
+    ///     best_value: 250.0,
+    /// };
+    /// assert_eq!(review.regret(), 40.0);
+    /// assert!(review.is_blunder());
+    /// ```
+    pub fn regret(&self) -> f64 {
+        (self.best_value - self.value).max(0.0)
+    }
+
+    pub fn is_blunder(&self) -> bool {
+        self.regret() >= BLUNDER
+    }
+}
+
+/// Reasons a transcript can't be analyzed
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum AnalysisError {
+    /// only games of five six-sided dice have been solved
+    Rules(Rules),
+    /// the move at an index couldn't have been made
+    InvalidMove { index: usize, reason: &'static str },
+}
+
+impl fmt::Display for AnalysisError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnalysisError::Rules(rules) => write!(
+                f,
+                "only games of 5d6 can be analyzed, this game was played with {}",
+                rules
+            ),
+            AnalysisError::InvalidMove { index, reason } => {
+                write!(f, "move {} is not valid: {}", index + 1, reason)
+            }
+        }
+    }
+}
+
+/// Replays a transcript and compares every decision in it with the best one
+/// according to the solver.
+///
+/// # Example
+/// ```rust,no_run
+/// use std::path::Path;
+/// use yahtzee::analysis;
+/// use yahtzee::solver::Solver;
+/// use yahtzee::transcript::Transcript;
+///
+/// let transcript = Transcript::load(Path::new("last_game.json")).unwrap();
+/// let reviews = analysis::analyze(&transcript, &Solver::new()).unwrap();
+/// for review in analysis::blunders(&reviews, 3) {
+///     println!("{} instead of {}", review.played.decision, review.best);
+/// }
+/// ```
+pub fn analyze(transcript: &Transcript, solver: &Solver) -> Result<Vec<Review>, AnalysisError> {
+    if !transcript.rules.is_standard() {
+        return Err(AnalysisError::Rules(transcript.rules));
+    }
+
+    let mut players: Vec<Player> = transcript
+        .players
+        .iter()
+        .map(|name| Player::new(name.clone()))
+        .collect();
+    // the turn's values only change once someone scores
+    let mut cached: Option<Turn> = None;
+    let mut reviews = Vec::with_capacity(transcript.moves.len());
+
+    for (index, played) in transcript.moves.iter().enumerate() {
+        let invalid = |reason| AnalysisError::InvalidMove { index, reason };
+        let player = players
+            .get_mut(played.player)
+            .ok_or_else(|| invalid("there is no such player"))?;
+        player.dice = played.dice.clone();
+
+        let card = solver.card(player).expect("the rules are checked above");
+        let turn = match cached.take() {
+            Some(turn) if turn.card() == card => turn,
+            _ => solver.turn(card),
+        };
+
+        let review = review(&turn, index, played)?;
+        if let Decision::Score(category) = played.decision {
+            let score = player
+                .possible_score(category)
+                .ok_or_else(|| invalid("the category can't be scored"))?;
+            player.update_score(score);
+        }
+        reviews.push(review);
+        cached = Some(turn);
+    }
+    Ok(reviews)
+}
+
+/// Compares one decision with the best one for the turn it was made in.
+///
+/// # Example
+/// ```rust
+/// use yahtzee::analysis;
+/// use yahtzee::score::Category;
+/// use yahtzee::solver::{Card, Solver};
+/// use yahtzee::transcript::{Decision, Move};
+///
+/// let solver = Solver::endgame(1);
+/// let card = Card { filled: 0x1fff & !(1 << Category::Chance.index()), upper: 0, yahtzee_bonus: false };
+/// let played = Move {
+///     player: 0,
+///     round: 13,
+///     dice: vec![6, 6, 1, 2, 5],
+///     rolls_left: 2,
+///     decision: Decision::Hold(vec![1, 2, 6, 6]),
+/// };
+/// let review = analysis::review(&solver.turn(card), 0, &played).unwrap();
+/// assert_eq!(review.best, Decision::Hold(vec![5, 6, 6]));
+/// assert!((review.regret() - 2.25).abs() < 1e-6);
+/// ```
+pub fn review(turn: &Turn, index: usize, played: &Move) -> Result<Review, AnalysisError> {
+    let invalid = |reason| AnalysisError::InvalidMove { index, reason };
+    let hand = DiceHand::from_dice(&played.dice).ok_or_else(|| invalid("the dice aren't 5d6"))?;
+
+    let rolls_left = played.rolls_left;
+    let (best, best_value) = best_decision(turn, hand, rolls_left)
+        .ok_or_else(|| invalid("there is nothing left to score"))?;
+
+    let value = match &played.decision {
+        Decision::Hold(_) if rolls_left == 0 => return Err(invalid("there are no rolls left")),
+        Decision::Hold(held) => {
+            let keep = Keep::from_dice(held)
+                .filter(|keep| hand.keeps().contains(keep))
+                .ok_or_else(|| invalid("the held dice aren't in the roll"))?;
+            turn.keep_value(keep, rolls_left)
+        }
+        Decision::Score(category) => turn
+            .score_value(hand, *category)
+            .ok_or_else(|| invalid("the category can't be scored"))?,
+    };
+
+    Ok(Review {
+        index,
+        played: played.clone(),
+        value,
+        best,
+        best_value,
+    })
+}
+
+/// The best decision for a hand with rolls left and its value, preferring to
+/// score when holding is no better, or None if there's nothing left to score.
+///
+/// # Example
+/// ```rust
+/// use yahtzee::analysis;
+/// use yahtzee::hand::DiceHand;
+/// use yahtzee::score::Category;
+/// use yahtzee::solver::{Card, Solver};
+/// use yahtzee::transcript::Decision;
+///
+/// let solver = Solver::endgame(1);
+/// let card = Card { filled: 0x1fff & !(1 << Category::Chance.index()), upper: 0, yahtzee_bonus: false };
+/// let turn = solver.turn(card);
+/// let hand = DiceHand::from_dice(&[6, 6, 6, 6, 5]).unwrap();
+/// let (decision, value) = analysis::best_decision(&turn, hand, 2).unwrap();
+/// assert_eq!(decision, Decision::Score(Category::Chance));
+/// assert_eq!(value, 29.0);
+/// ```
+pub fn best_decision(turn: &Turn, hand: DiceHand, rolls_left: u32) -> Option<(Decision, f64)> {
+    let (best_category, score_value) = turn.best_score(hand)?;
+    Some(match rolls_left {
+        0 => (Decision::Score(best_category), score_value),
+        _ => match turn.best_keep(hand, rolls_left) {
+            (keep, value) if value > score_value + EPSILON => (Decision::Hold(keep.dice()), value),
+            _ => (Decision::Score(best_category), score_value),
+        },
+    })
+}
+
+/// the total regret of every decision made by a player
+pub fn total_regret(reviews: &[Review], player: usize) -> f64 {
+    reviews
+        .iter()
+        .filter(|review| review.played.player == player)
+        .map(Review::regret)
+        .sum()
+}
+
+/// up to `count` blunders, the most costly first
+pub fn blunders(reviews: &[Review], count: usize) -> Vec<&Review> {
+    let mut blunders: Vec<&Review> = reviews
+        .iter()
+        .filter(|review| review.is_blunder())
+        .collect();
+    blunders.sort_by(|a, b| b.regret().total_cmp(&a.regret()));
+    blunders.truncate(count);
+    blunders
+}
@@ -0,0 +1,164 @@
+use crate::player::Player;
+use crate::score::{Category, Score};
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use std::{fmt, str};
+
+/// A source of the time, so timed games can be played against a fake clock.
+pub trait Clock {
+    /// the time since some fixed point, which only moves forward
+    fn now(&self) -> Duration;
+}
+
+/// The real time, measured from when the program first read it
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        static START: OnceLock<Instant> = OnceLock::new();
+        START.get_or_init(Instant::now).elapsed()
+    }
+}
+
+/// A clock that only moves when it's told to
+///
+/// # Example
+/// ```rust
+/// use std::time::Duration;
+/// use yahtzee::clock::{Clock, FakeClock};
+///
+/// let clock = FakeClock::default();
+/// clock.advance(Duration::from_secs(5));
+/// assert_eq!(clock.now(), Duration::from_secs(5));
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct FakeClock {
+    now: Cell<Duration>,
+}
+
+impl FakeClock {
+    pub fn advance(&self, by: Duration) {
+        self.now.set(self.now.get() + by);
+    }
+}
+
+impl Clock for FakeClock {
+    fn now(&self) -> Duration {
+        self.now.get()
+    }
+}
+
+/// What's scored for a player who runs out of time
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub enum Fallback {
+    /// the open box worth the most points
+    #[default]
+    Best,
+    /// the open box worth the fewest points
+    Lowest,
+}
+
+impl Fallback {
+    /// The box to score for a player out of time, ties going to the box
+    /// listed first among the possible scores.
+    ///
+    /// # Example
+    /// ```rust
+    /// use yahtzee::clock::Fallback;
+    /// use yahtzee::player::Player;
+    /// use yahtzee::score::Category;
+    ///
+    /// let mut player = Player::new("Ann".to_owned());
+    /// player.set_dice(vec![2, 2, 2, 5, 5]).unwrap();
+    /// assert_eq!(Fallback::Best.category(&player), Some(Category::FullHouse));
+    /// assert_eq!(Fallback::Lowest.category(&player), Some(Category::Aces));
+    /// ```
+    pub fn category(self, player: &Player) -> Option<Category> {
+        let scores = player.possible_scores();
+        let score = match self {
+            // max_by_key keeps the last of equal scores
+            Fallback::Best => scores.iter().rev().max_by_key(|score| score.value()),
+            Fallback::Lowest => scores.iter().min_by_key(|score| score.value()),
+        };
+        score.and_then(Score::category)
+    }
+}
+
+impl fmt::Display for Fallback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Fallback::Best => write!(f, "best"),
+            Fallback::Lowest => write!(f, "lowest"),
+        }
+    }
+}
+
+/// The text given wasn't the name of a fallback
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ParseFallbackError(pub String);
+
+impl fmt::Display for ParseFallbackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown fallback \"{}\", use best or lowest", self.0)
+    }
+}
+
+impl str::FromStr for Fallback {
+    type Err = ParseFallbackError;
+
+    fn from_str(input: &str) -> Result<Fallback, ParseFallbackError> {
+        match input.trim().to_lowercase().as_str() {
+            "best" => Ok(Fallback::Best),
+            "lowest" => Ok(Fallback::Lowest),
+            _ => Err(ParseFallbackError(input.to_owned())),
+        }
+    }
+}
+
+/// How long players have to play, chess clock style
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub struct TimeControl {
+    /// the time allowed for each turn
+    pub per_turn: Option<Duration>,
+    /// the time each player has for all their turns
+    pub per_game: Option<Duration>,
+    pub fallback: Fallback,
+}
+
+impl TimeControl {
+    /// quick games for when there isn't long to play
+    pub const BLITZ: TimeControl = TimeControl {
+        per_turn: Some(Duration::from_secs(20)),
+        per_game: Some(Duration::from_secs(180)),
+        fallback: Fallback::Best,
+    };
+
+    /// Whichever runs out first of the turn's time and the player's time for
+    /// the game, after `used` of the game and `elapsed` of the turn.
+    ///
+    /// # Example
+    /// ```rust
+    /// use std::time::Duration;
+    /// use yahtzee::clock::TimeControl;
+    ///
+    /// let seconds = Duration::from_secs;
+    /// let blitz = TimeControl::BLITZ;
+    /// assert_eq!(blitz.time_left(seconds(0), seconds(5)), seconds(15));
+    /// assert_eq!(blitz.time_left(seconds(170), seconds(5)), seconds(5));
+    /// assert_eq!(blitz.time_left(seconds(0), seconds(25)), seconds(0));
+    /// ```
+    pub fn time_left(&self, used: Duration, elapsed: Duration) -> Duration {
+        let turn = self.per_turn.map(|turn| turn.saturating_sub(elapsed));
+        let game = self
+            .per_game
+            .map(|game| game.saturating_sub(used + elapsed));
+        match (turn, game) {
+            (Some(turn), Some(game)) => turn.min(game),
+            (Some(left), None) | (None, Some(left)) => left,
+            (None, None) => Duration::MAX,
+        }
+    }
+}
@@ -0,0 +1,369 @@
+use crate::game::{Game, GameError};
+use crate::rules::Rules;
+use crate::transcript::{Decision, Move, Transcript};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// the name of the file in the data directory a day's transcript is saved to
+pub fn transcript_file(date: Date) -> String {
+    format!("daily-{}.json", date)
+}
+
+/// A day of the daily challenge
+///
+/// # Example
+/// ```rust
+/// use yahtzee::daily::Date;
+///
+/// let date: Date = "2024-02-29".parse().unwrap();
+/// assert_eq!(date, Date { year: 2024, month: 2, day: 29 });
+/// assert_eq!(date.to_string(), "2024-02-29");
+/// assert!("2023-02-29".parse::<Date>().is_err());
+/// assert!("29/02/2024".parse::<Date>().is_err());
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub struct Date {
+    pub year: u32,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl Date {
+    /// Today's date in UTC, so players in every time zone share the same
+    /// challenge.
+    pub fn today() -> Date {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or(0);
+        Date::from_days(seconds / SECONDS_PER_DAY)
+    }
+
+    /// the date a number of days after 1970-01-01
+    ///
+    /// # Example
+    /// ```rust
+    /// use yahtzee::daily::Date;
+    ///
+    /// assert_eq!(Date::from_days(0).to_string(), "1970-01-01");
+    /// assert_eq!(Date::from_days(19_782).to_string(), "2024-02-29");
+    /// ```
+    pub fn from_days(days: u64) -> Date {
+        // shift to a calendar starting in March so leap days come last
+        let days = days + 719_468;
+        let era = days / 146_097;
+        let day_of_era = days % 146_097;
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+        let month = if shifted_month < 10 {
+            shifted_month + 3
+        } else {
+            shifted_month - 9
+        };
+        let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+        Date {
+            year: year as u32,
+            month: month as u32,
+            day: day as u32,
+        }
+    }
+
+    /// the seed every roll of the day's game is derived from
+    pub fn seed(&self) -> u64 {
+        fnv1a(format!("yahtzee daily {}", self).as_bytes())
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// Error for text that isn't a date written as YYYY-MM-DD
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ParseDateError(String);
+
+impl fmt::Display for ParseDateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} is not a date like 2024-02-29", self.0)
+    }
+}
+
+impl FromStr for Date {
+    type Err = ParseDateError;
+
+    fn from_str(s: &str) -> Result<Date, ParseDateError> {
+        let error = || ParseDateError(s.to_owned());
+        let parts: Vec<u32> = s
+            .split('-')
+            .map(str::parse)
+            .collect::<Result<_, _>>()
+            .map_err(|_| error())?;
+        let date = match parts.as_slice() {
+            [year, month, day] => Date {
+                year: *year,
+                month: *month,
+                day: *day,
+            },
+            _ => return Err(error()),
+        };
+
+        let leap_year = date.year % 4 == 0 && (date.year % 100 != 0 || date.year % 400 == 0);
+        let days_in_month = match date.month {
+            2 if leap_year => 29,
+            2 => 28,
+            4 | 6 | 9 | 11 => 30,
+            1..=12 => 31,
+            _ => return Err(error()),
+        };
+        if date.day < 1 || date.day > days_in_month {
+            return Err(error());
+        }
+        Ok(date)
+    }
+}
+
+/// Rolls the dice for one roll of one round of a seeded game.
+///
+/// Every roll has its own seed, so the dice don't depend on what was held
+/// earlier and every player sees the same dice in the same round.
+///
+/// # Example
+/// ```rust
+/// use yahtzee::daily::{self, Date};
+/// use yahtzee::rules::Rules;
+///
+/// let seed = Date { year: 2024, month: 2, day: 29 }.seed();
+/// let dice = daily::dice(seed, 1, 0, &Rules::default());
+/// assert_eq!(dice.len(), 5);
+/// assert!(dice.iter().all(|die| (1..=6).contains(die)));
+/// assert_eq!(daily::dice(seed, 1, 0, &Rules::default()), dice);
+/// assert_ne!(daily::dice(seed, 1, 1, &Rules::default()), dice);
+/// ```
+pub fn dice(seed: u64, round: usize, roll: u32, rules: &Rules) -> Vec<u32> {
+    let mut state = seed ^ ((round as u64) << 8 | u64::from(roll));
+    let mut state = split_mix(&mut state);
+    (0..rules.num_dice)
+        .map(|_| (split_mix(&mut state) % u64::from(rules.num_faces)) as u32 + 1)
+        .collect()
+}
+
+/// One player's daily challenge score, with a fingerprint of the moves that
+/// got it
+///
+/// # Example
+/// ```rust
+/// use yahtzee::daily::{DailyResult, Date};
+///
+/// let result = DailyResult { date: Date { year: 2024, month: 2, day: 29 }, score: 254, hash: 0x9f3c };
+/// let text = result.to_string();
+/// assert_eq!(text, "yahtzee daily 2024-02-29 score 254 moves 0000000000009f3c");
+/// assert_eq!(text.parse(), Ok(result));
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct DailyResult {
+    pub date: Date,
+    pub score: u32,
+    /// hash of every move the player made
+    pub hash: u64,
+}
+
+impl DailyResult {
+    /// the result of one player in a finished daily game
+    pub fn new(date: Date, game: &Game, player: usize) -> DailyResult {
+        DailyResult {
+            date,
+            score: game.players[player].score,
+            hash: moves_hash(&player_moves(&game.moves, player)),
+        }
+    }
+}
+
+impl fmt::Display for DailyResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "yahtzee daily {} score {} moves {:016x}",
+            self.date, self.score, self.hash
+        )
+    }
+}
+
+/// Error for text that isn't a daily result
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ParseResultError(String);
+
+impl fmt::Display for ParseResultError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} is not a daily result", self.0)
+    }
+}
+
+impl FromStr for DailyResult {
+    type Err = ParseResultError;
+
+    fn from_str(s: &str) -> Result<DailyResult, ParseResultError> {
+        let error = || ParseResultError(s.to_owned());
+        let words: Vec<&str> = s.split_whitespace().collect();
+        match words.as_slice() {
+            ["yahtzee", "daily", date, "score", score, "moves", hash] => Ok(DailyResult {
+                date: date.parse().map_err(|_| error())?,
+                score: score.parse().map_err(|_| error())?,
+                hash: u64::from_str_radix(hash, 16).map_err(|_| error())?,
+            }),
+            _ => Err(error()),
+        }
+    }
+}
+
+/// Reasons a daily result doesn't check out
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum VerifyError {
+    /// no player in the transcript made the moves the result was for
+    NoMatchingMoves,
+    /// the dice of the move at an index aren't the ones rolled that day
+    WrongDice { index: usize },
+    /// the move at an index couldn't have been made
+    InvalidMove { index: usize, error: GameError },
+    /// the moves stop before the game is over
+    Unfinished,
+    /// the moves add up to a different score
+    WrongScore { claimed: u32, actual: u32 },
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerifyError::NoMatchingMoves => {
+                write!(f, "nobody in the transcript made the moves in the result")
+            }
+            VerifyError::WrongDice { index } => {
+                write!(f, "move {} wasn't made with that day's dice", index + 1)
+            }
+            VerifyError::InvalidMove { index, error } => {
+                write!(f, "move {} is not valid: {}", index + 1, error)
+            }
+            VerifyError::Unfinished => write!(f, "the game wasn't finished"),
+            VerifyError::WrongScore { claimed, actual } => {
+                write!(f, "the moves score {} points, not {}", actual, claimed)
+            }
+        }
+    }
+}
+
+/// Checks a daily result against the transcript of the game it came from by
+/// replaying the player's moves with that day's dice, returning the name of
+/// the player it belongs to.
+///
+/// # Example
+/// ```rust
+/// use yahtzee::daily::{self, DailyResult, Date, VerifyError};
+/// use yahtzee::game::Game;
+/// use yahtzee::rules::Rules;
+///
+/// let date = Date { year: 2024, month: 2, day: 29 };
+/// let mut game = Game::new(Rules::default(), vec!["Ann".to_owned()]);
+/// game.daily = Some(date);
+/// let mut rng = rand::thread_rng();
+/// while !game.is_over() {
+///     game.roll(&mut rng).unwrap();
+///     let best = game.current_player().possible_scores().into_iter().max_by_key(|score| score.value()).unwrap();
+///     game.score(best.category().unwrap()).unwrap();
+/// }
+/// game.finish();
+///
+/// let mut result = DailyResult::new(date, &game, 0);
+/// assert_eq!(daily::verify(&result, &game.transcript()), Ok("Ann".to_owned()));
+/// result.score += 1;
+/// assert!(matches!(daily::verify(&result, &game.transcript()), Err(VerifyError::WrongScore { .. })));
+/// result.date.day = 28;
+/// assert_eq!(daily::verify(&result, &game.transcript()), Err(VerifyError::WrongDice { index: 0 }));
+/// ```
+pub fn verify(result: &DailyResult, transcript: &Transcript) -> Result<String, VerifyError> {
+    let player = (0..transcript.players.len())
+        .find(|player| moves_hash(&player_moves(&transcript.moves, *player)) == result.hash)
+        .ok_or(VerifyError::NoMatchingMoves)?;
+    let name = transcript.players[player].clone();
+
+    let mut game = Game::new(Rules::default(), vec![name.clone()]);
+    game.daily = Some(result.date);
+    // seeded games never draw from the generator
+    let mut rng = rand::thread_rng();
+    for (index, played) in player_moves(&transcript.moves, player).iter().enumerate() {
+        let invalid = |error| VerifyError::InvalidMove { index, error };
+        if game.rolls == 0 {
+            game.roll(&mut rng).map_err(invalid)?;
+        }
+        if sorted(&game.current_player().dice) != sorted(&played.dice)
+            || game.round != played.round
+            || game.rolls_left() != played.rolls_left
+        {
+            return Err(VerifyError::WrongDice { index });
+        }
+        match &played.decision {
+            Decision::Hold(held) => game.keep(held, &mut rng).map_err(invalid)?,
+            Decision::Score(category) => game.score(*category).map(|_| ()).map_err(invalid)?,
+        }
+    }
+
+    if !game.is_over() {
+        return Err(VerifyError::Unfinished);
+    }
+    game.finish();
+    match game.players[0].score {
+        actual if actual == result.score => Ok(name),
+        actual => Err(VerifyError::WrongScore {
+            claimed: result.score,
+            actual,
+        }),
+    }
+}
+
+fn sorted(dice: &[u32]) -> Vec<u32> {
+    let mut dice = dice.to_vec();
+    dice.sort_unstable();
+    dice
+}
+
+/// the moves made by one player, as if they had played alone
+fn player_moves(moves: &[Move], player: usize) -> Vec<Move> {
+    moves
+        .iter()
+        .filter(|played| played.player == player)
+        .map(|played| Move {
+            player: 0,
+            ..played.clone()
+        })
+        .collect()
+}
+
+fn moves_hash(moves: &[Move]) -> u64 {
+    let moves = serde_json::to_string(moves).expect("moves can always be written as JSON");
+    fnv1a(moves.as_bytes())
+}
+
+/// 64 bit FNV-1a, which unlike the standard library's hasher gives the same
+/// hash on every platform and version
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// the next number from a SplitMix64 generator
+fn split_mix(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
@@ -0,0 +1,210 @@
+use crate::hand::{self, DiceHand, NUM_KEEPS};
+use crate::score::Category;
+use crate::solver::{self, Card, Solved, Solver, Turn};
+use crate::strategy::TIE_BREAK;
+use std::collections::HashMap;
+
+/// turns left on a card at which chances start being worked out exactly
+/// rather than estimated from the spread of scores
+pub const EXACT_TURNS: usize = 4;
+
+/// The exact chance of each number of points still to come from a scorecard
+/// when playing for the highest average, for the last few turns of a game.
+///
+/// # Example
+/// ```rust
+/// use yahtzee::score::Category;
+/// use yahtzee::solver::{Card, Solver};
+/// use yahtzee::endgame::EndgamePoints;
+///
+/// let solver = Solver::endgame(1);
+/// let mut endgame = EndgamePoints::new(&solver);
+///
+/// // only chance is left, which scores from 5 to 30
+/// let card = Card { filled: !(1 << Category::Chance.index()) & 0x1fff, upper: 0, yahtzee_bonus: false };
+/// let points = endgame.points(card);
+/// assert_eq!(points.len(), 31);
+/// assert!(points[..5].iter().all(|chance| *chance == 0.0));
+/// assert!((points.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+/// let mean: f64 = points.iter().enumerate().map(|(points, chance)| points as f64 * chance).sum();
+/// assert!((mean - solver.value(card)).abs() < 1e-3);
+/// ```
+#[derive(Debug, Clone)]
+pub struct EndgamePoints<'a> {
+    solver: &'a Solver,
+    /// the chances already worked out for each card
+    points: HashMap<Card, Vec<f64>>,
+}
+
+impl<'a> EndgamePoints<'a> {
+    pub fn new(solver: &'a Solver) -> EndgamePoints<'a> {
+        EndgamePoints {
+            solver,
+            points: HashMap::new(),
+        }
+    }
+
+    /// The chance of scoring each number of points from the start of a turn
+    /// on a card, indexed by the points. Cards with more than `EXACT_TURNS`
+    /// turns left take a long time to work out.
+    pub fn points(&mut self, card: Card) -> Vec<f64> {
+        if card.turns_left() == 0 {
+            return vec![1.0];
+        }
+        if let Some(points) = self.points.get(&card) {
+            return points.clone();
+        }
+
+        let solver = self.solver;
+        let turn = solver.turn(card);
+        let mut scored = Vec::with_capacity(hand::NUM_HANDS);
+        for hand in DiceHand::all() {
+            let (category, _) = turn
+                .best_score(hand)
+                .expect("a card with turns left has a box open");
+            let (points, next) = solver
+                .after_score(card, hand, category)
+                .expect("the best score can be scored");
+            let mut spread = vec![0.0; points as usize];
+            spread.extend(self.points(next));
+            scored.push(spread);
+        }
+        let hands_1 = held(&turn, &scored, 1);
+        let hands_2 = held(&turn, &hands_1, 2);
+        let points = mix(hand::first_roll()
+            .iter()
+            .map(|(hand, probability)| (*probability, &hands_2[hand.index()])));
+        self.points.insert(card, points.clone());
+        points
+    }
+}
+
+/// the chances from each hand with rolls left, holding the dice the turn
+/// holds and going on to `hands` with one roll fewer
+fn held(turn: &Turn, hands: &[Vec<f64>], rolls_left: u32) -> Vec<Vec<f64>> {
+    let mut keeps: Vec<Option<Vec<f64>>> = vec![None; NUM_KEEPS];
+    DiceHand::all()
+        .map(|hand| {
+            let (keep, _) = turn.best_keep(hand, rolls_left);
+            keeps[keep.index()]
+                .get_or_insert_with(|| {
+                    mix(keep
+                        .outcomes()
+                        .iter()
+                        .map(|(hand, probability)| (*probability, &hands[hand.index()])))
+                })
+                .clone()
+        })
+        .collect()
+}
+
+/// the chances of each number of points when each spread of them comes up
+/// with some probability
+fn mix<'b, I>(spreads: I) -> Vec<f64>
+where
+    I: Iterator<Item = (f64, &'b Vec<f64>)>,
+{
+    let mut mixed: Vec<f64> = Vec::new();
+    for (probability, spread) in spreads {
+        if mixed.len() < spread.len() {
+            mixed.resize(spread.len(), 0.0);
+        }
+        for (mixed, chance) in mixed.iter_mut().zip(spread) {
+            *mixed += probability * chance;
+        }
+    }
+    mixed
+}
+
+/// Plays to finish ahead of a rival whose chance of each final score is
+/// known, working chances out exactly over the last few turns of a game.
+/// Finishing level counts as half a win.
+///
+/// # Example
+/// ```rust
+/// use yahtzee::score::Category;
+/// use yahtzee::solver::{Card, Solved, Solver, Spread};
+/// use yahtzee::endgame::Showdown;
+///
+/// let solver = Solver::endgame(1);
+/// let spread = Spread::new(&solver);
+/// let solved = Solved { solver, spread };
+///
+/// // the rival finished on 25
+/// let mut finals = vec![0.0; 26];
+/// finals[25] = 1.0;
+/// let mut showdown = Showdown::new(&solved, &finals);
+///
+/// let card = Card { filled: !(1 << Category::Chance.index()) & 0x1fff, upper: 0, yahtzee_bonus: false };
+/// assert_eq!(showdown.chance(card, 200), 1.0);
+/// assert!(showdown.chance(card, 0) < showdown.chance(card, 5));
+/// assert!(showdown.chance(card, 0) < 0.5);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Showdown<'a> {
+    solved: &'a Solved,
+    /// the chance each final score wins, with scores past the end sure to
+    wins: Vec<f64>,
+    /// chances already worked out for a card and the points already scored
+    chances: HashMap<(Card, u32), f64>,
+}
+
+impl<'a> Showdown<'a> {
+    /// a showdown with a rival who finishes on each score with the chance
+    /// at that index of `finals`
+    pub fn new(solved: &'a Solved, finals: &[f64]) -> Showdown<'a> {
+        let mut below = 0.0;
+        let wins = finals
+            .iter()
+            .map(|chance| {
+                let win = below + chance / 2.0;
+                below += chance;
+                win
+            })
+            .collect();
+        Showdown {
+            solved,
+            wins,
+            chances: HashMap::new(),
+        }
+    }
+
+    /// the chance of finishing ahead of the rival from the start of a turn
+    /// on a card with points already scored
+    pub fn chance(&mut self, card: Card, score: u32) -> f64 {
+        if card.turns_left() == 0 {
+            return self.wins.get(score as usize).copied().unwrap_or(1.0);
+        }
+        if let Some(chance) = self.chances.get(&(card, score)) {
+            return *chance;
+        }
+
+        let solved = self.solved;
+        let mut scored = Vec::with_capacity(hand::NUM_HANDS);
+        for hand in DiceHand::all() {
+            let mut best = f64::MIN;
+            for category in Category::STANDARD.iter() {
+                if let Some((points, next)) = solved.solver.after_score(card, hand, *category) {
+                    best = best.max(self.after(next, score + points));
+                }
+            }
+            scored.push(best);
+        }
+        let hands_1 = solver::hand_values(&solver::keep_values(&scored));
+        let hands_2 = solver::hand_values(&solver::keep_values(&hands_1));
+        let chance = hand::first_roll()
+            .iter()
+            .map(|(hand, probability)| probability * hands_2[hand.index()])
+            .sum::<f64>()
+            .min(1.0);
+        self.chances.insert((card, score), chance);
+        chance
+    }
+
+    /// the chance of finishing ahead after moving on to a card with points
+    /// scored, with a share of the expected final score to settle ties
+    pub fn after(&mut self, next: Card, score: u32) -> f64 {
+        let expected = f64::from(score) + self.solved.solver.value(next);
+        self.chance(next, score) + TIE_BREAK * expected
+    }
+}
@@ -0,0 +1,634 @@
+//! Bots run as separate programs, which can be written in any language.
+//!
+//! The game and an engine talk over the engine's standard input and output,
+//! one line at a time. The game starts with
+//!
+//! ```text
+//! yahtzee 1
+//! ```
+//!
+//! and the engine answers `ready` once it can play. Anything it prints
+//! before then is ignored. For each decision the game then sends the
+//! position:
+//!
+//! ```text
+//! move rolls=2 dice=6,6,1,2,5 open=1,2,5,6,3k,4k,ch,yz me=37/3:9,4:12,fh:25 opponent=41/6:24,ss:30
+//! ```
+//!
+//! - `rolls` is the number of rerolls left, with 0 meaning a score has to be
+//!   picked
+//! - `dice` is the dice in order
+//! - `open` is the code of every box the dice can be scored in
+//! - `me` is the engine's own scorecard, as its total and then the box and
+//!   points of each score in the order they were taken, with a bonus
+//!   Yahtzee written as `yz:150`
+//! - `opponent` is another player's scorecard in the same form, repeated for
+//!   each of them
+//!
+//! The engine answers with either
+//!
+//! ```text
+//! hold 6 6 5
+//! score ch
+//! ```
+//!
+//! holding the dice with those values and rerolling the rest, or scoring the
+//! dice in a box given by its code or name. Holding every die stops rolling,
+//! and the game asks again with no rolls left. The game sends `quit` when it
+//! is done with the engine.
+//!
+//! An answer that isn't a legal move is a fault, and the game makes a safe
+//! move instead: holding everything, or taking the box worth the most points.
+//! An engine that doesn't answer in time or stops running is no longer asked
+//! and plays safe moves for the rest of the game.
+
+use crate::game::ROLLS_PER_TURN;
+use crate::hand::DiceHand;
+use crate::player::Player;
+use crate::score::{Category, Score};
+use crate::strategy::{self, Greedy, Strategy};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::Duration;
+use std::{fmt, thread};
+
+/// the version of the protocol sent in the greeting
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// how long an engine has to answer each move by default
+pub const MOVE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// how long an engine has to answer the greeting, which allows for loading
+/// or working out any tables it plays from
+pub const START_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Reasons an engine couldn't be started
+#[derive(Debug)]
+pub enum EngineError {
+    /// the command was empty
+    NoCommand,
+    /// the program couldn't be run
+    Spawn(io::Error),
+    /// the program didn't say it was ready in time
+    NotReady,
+    /// the program stopped before saying it was ready
+    Closed,
+}
+
+impl fmt::Display for EngineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EngineError::NoCommand => write!(f, "no engine command was given"),
+            EngineError::Spawn(error) => write!(f, "the engine couldn't be run: {}", error),
+            EngineError::NotReady => write!(f, "the engine didn't say it was ready in time"),
+            EngineError::Closed => write!(f, "the engine stopped before it was ready"),
+        }
+    }
+}
+
+/// Something an engine got wrong during a game
+///
+/// # Example
+/// ```rust
+/// use std::io::{self, BufRead, Write};
+/// use std::thread;
+/// use std::time::Duration;
+/// use yahtzee::engine::{Engine, Fault};
+/// use yahtzee::player::Player;
+/// use yahtzee::score::Score;
+/// use yahtzee::strategy::Strategy;
+///
+/// // an engine that holds dice it doesn't have, then stops answering
+/// let (requests, engine_input) = io::pipe().unwrap();
+/// let (answers, mut engine_output) = io::pipe().unwrap();
+/// thread::spawn(move || {
+///     let mut requests = io::BufReader::new(requests).lines();
+///     requests.next();
+///     writeln!(engine_output, "ready").unwrap();
+///     requests.next();
+///     writeln!(engine_output, "hold 4 4").unwrap();
+///     for _ in requests {}
+/// });
+///
+/// let mut engine = Engine::connect(answers, engine_input, Duration::from_millis(50)).unwrap();
+/// let mut player = Player::new("engine".to_owned());
+/// player.dice = vec![4, 2, 3, 5, 6];
+/// assert!(engine.reroll(&player, 2).is_empty());
+/// assert_eq!(engine.score(&player, &player.possible_scores()), Score::LargeStraight(40));
+/// assert_eq!(engine.faults()[1], Fault::Timeout);
+/// assert!(matches!(&engine.faults()[0], Fault::Illegal { answer, .. } if answer == "hold 4 4"));
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Fault {
+    /// the engine took too long to answer, and isn't asked again
+    Timeout,
+    /// the engine stopped running
+    Disconnected,
+    /// the answer wasn't a legal move
+    Illegal { answer: String, reason: String },
+}
+
+impl fmt::Display for Fault {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Fault::Timeout => write!(f, "the engine took too long to answer"),
+            Fault::Disconnected => write!(f, "the engine stopped running"),
+            Fault::Illegal { answer, reason } => {
+                write!(f, "the engine answered \"{}\": {}", answer, reason)
+            }
+        }
+    }
+}
+
+/// A move sent back by an engine
+#[derive(Debug, PartialEq, Eq, Clone)]
+enum Answer {
+    /// the values of the dice to hold
+    Hold(Vec<u32>),
+    Score(Category),
+}
+
+impl Answer {
+    fn parse(line: &str) -> Result<Answer, String> {
+        let mut words = line.split_whitespace();
+        match words.next() {
+            Some("hold") => words
+                .map(|value| value.parse().map_err(|_| format!("{} is not a die", value)))
+                .collect::<Result<_, _>>()
+                .map(Answer::Hold),
+            Some("score") => {
+                let name: Vec<&str> = words.collect();
+                name.join(" ")
+                    .parse()
+                    .map(Answer::Score)
+                    .map_err(|error| format!("{}", error))
+            }
+            _ => Err("expected hold or score".to_owned()),
+        }
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Hold(dice) => {
+                write!(f, "hold")?;
+                dice.iter().try_for_each(|die| write!(f, " {}", die))
+            }
+            Answer::Score(category) => write!(f, "score {}", category.code()),
+        }
+    }
+}
+
+/// A strategy played by an engine running in another program.
+///
+/// # Example
+/// ```rust
+/// use rand::rngs::StdRng;
+/// use rand::SeedableRng;
+/// use std::{io, thread};
+/// use yahtzee::engine::{self, Engine, MOVE_TIMEOUT};
+/// use yahtzee::strategy::{self, Greedy, Strategy};
+///
+/// // the reference engine, serving the greedy bot over a pair of pipes
+/// let (requests, engine_input) = io::pipe().unwrap();
+/// let (answers, engine_output) = io::pipe().unwrap();
+/// thread::spawn(move || engine::serve(&mut Greedy, io::BufReader::new(requests), engine_output));
+///
+/// let mut engine = Engine::connect(answers, engine_input, MOVE_TIMEOUT).unwrap();
+/// let played = strategy::play_game("engine".to_owned(), &mut engine, &mut StdRng::seed_from_u64(4));
+/// let greedy = strategy::play_game("greedy".to_owned(), &mut Greedy, &mut StdRng::seed_from_u64(4));
+/// assert_eq!(played.scores, greedy.scores);
+/// assert!(engine.faults().is_empty());
+/// ```
+pub struct Engine {
+    /// the program, if the engine is one this started
+    child: Option<Child>,
+    requests: Box<dyn Write + Send>,
+    /// lines from the engine, read on another thread so reads can time out
+    answers: Receiver<String>,
+    timeout: Duration,
+    /// whether the engine is still asked for moves
+    connected: bool,
+    /// everyone in the game and the engine's seat, as of its turn starting
+    players: Vec<Player>,
+    seat: usize,
+    /// a box the engine picked while it still had rolls left
+    pending: Option<Category>,
+    faults: Vec<Fault>,
+}
+
+impl Engine {
+    /// Runs a command as an engine, split into the program and its arguments
+    /// on whitespace, and waits for it to be ready. An engine that stops
+    /// answering is killed.
+    ///
+    /// # Example
+    /// ```rust
+    /// use std::time::{Duration, Instant};
+    /// use std::{env, fs};
+    /// use yahtzee::engine::{Engine, EngineError, Fault};
+    /// use yahtzee::player::Player;
+    /// use yahtzee::strategy::Strategy;
+    ///
+    /// assert!(matches!(Engine::spawn(" ", Duration::from_secs(1)), Err(EngineError::NoCommand)));
+    /// assert!(matches!(
+    ///     Engine::spawn("no-such-yahtzee-engine", Duration::from_secs(1)),
+    ///     Err(EngineError::Spawn(_))
+    /// ));
+    /// assert!(matches!(Engine::spawn("true", Duration::from_secs(1)), Err(EngineError::Closed)));
+    ///
+    /// // an engine that says it's ready and then never answers
+    /// let script = env::temp_dir().join(format!("yahtzee-engine-{}.sh", std::process::id()));
+    /// fs::write(&script, "read greeting\necho ready\nexec sleep 60\n").unwrap();
+    /// let started = Instant::now();
+    /// let command = format!("sh {}", script.display());
+    /// let mut engine = Engine::spawn(&command, Duration::from_millis(100)).unwrap();
+    /// let mut player = Player::new("engine".to_owned());
+    /// player.dice = vec![1, 2, 3, 4, 6];
+    /// assert!(engine.reroll(&player, 2).is_empty());
+    /// assert_eq!(engine.faults(), &[Fault::Timeout]);
+    /// drop(engine);
+    /// fs::remove_file(&script).unwrap();
+    /// assert!(started.elapsed() < Duration::from_secs(30));
+    /// ```
+    pub fn spawn(command: &str, timeout: Duration) -> Result<Engine, EngineError> {
+        let mut words = command.split_whitespace();
+        let program = words.next().ok_or(EngineError::NoCommand)?;
+        let mut child = Command::new(program)
+            .args(words)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(EngineError::Spawn)?;
+        let input = child.stdin.take().expect("the input is piped");
+        let output = child.stdout.take().expect("the output is piped");
+
+        let mut engine = Engine::connect(output, input, timeout);
+        match engine.as_mut() {
+            Ok(engine) => engine.child = Some(child),
+            Err(_) => {
+                let _ = child.kill();
+                let _ = child.wait();
+            }
+        }
+        engine
+    }
+
+    /// Talks to an engine reading requests from `input` and answering on
+    /// `output`, and waits for it to be ready.
+    pub fn connect<R, W>(output: R, input: W, timeout: Duration) -> Result<Engine, EngineError>
+    where
+        R: Read + Send + 'static,
+        W: Write + Send + 'static,
+    {
+        let (sender, answers) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(output).lines().map_while(Result::ok) {
+                if sender.send(line).is_err() {
+                    return;
+                }
+            }
+        });
+
+        let mut engine = Engine {
+            child: None,
+            requests: Box::new(input),
+            answers,
+            timeout,
+            connected: true,
+            players: Vec::new(),
+            seat: 0,
+            pending: None,
+            faults: Vec::new(),
+        };
+        engine
+            .send(&format!("yahtzee {}", PROTOCOL_VERSION))
+            .map_err(|_| EngineError::Closed)?;
+        loop {
+            match engine.answers.recv_timeout(START_TIMEOUT.max(timeout)) {
+                Ok(line) if line.trim() == "ready" => return Ok(engine),
+                Ok(_) => {}
+                Err(RecvTimeoutError::Timeout) => return Err(EngineError::NotReady),
+                Err(RecvTimeoutError::Disconnected) => return Err(EngineError::Closed),
+            }
+        }
+    }
+
+    fn send(&mut self, line: &str) -> io::Result<()> {
+        writeln!(self.requests, "{}", line)?;
+        self.requests.flush()
+    }
+
+    /// asks the engine for a move, or None if it didn't give one
+    fn ask(&mut self, player: &Player, rolls_left: u32) -> Option<Answer> {
+        if !self.connected {
+            return None;
+        }
+        let opponents: Vec<&Player> = self
+            .players
+            .iter()
+            .enumerate()
+            .filter(|(seat, _)| *seat != self.seat)
+            .map(|(_, opponent)| opponent)
+            .collect();
+        if self.send(&request(player, &opponents, rolls_left)).is_err() {
+            self.disconnect(Fault::Disconnected);
+            return None;
+        }
+
+        match self.answers.recv_timeout(self.timeout) {
+            Ok(line) => match Answer::parse(&line) {
+                Ok(answer) => Some(answer),
+                Err(reason) => {
+                    self.illegal(&line, reason);
+                    None
+                }
+            },
+            Err(RecvTimeoutError::Timeout) => {
+                self.disconnect(Fault::Timeout);
+                None
+            }
+            Err(RecvTimeoutError::Disconnected) => {
+                self.disconnect(Fault::Disconnected);
+                None
+            }
+        }
+    }
+
+    fn illegal(&mut self, answer: &str, reason: String) {
+        self.faults.push(Fault::Illegal {
+            answer: answer.trim().to_owned(),
+            reason,
+        });
+    }
+
+    /// stops asking the engine for moves, since its answers can no longer
+    /// be matched up with the questions
+    fn disconnect(&mut self, fault: Fault) {
+        self.faults.push(fault);
+        self.connected = false;
+        if let Some(child) = self.child.as_mut() {
+            let _ = child.kill();
+        }
+    }
+}
+
+impl Strategy for Engine {
+    fn reroll(&mut self, player: &Player, rolls_left: u32) -> Vec<u8> {
+        self.pending = None;
+        match self.ask(player, rolls_left) {
+            Some(Answer::Hold(held)) if is_held(&player.dice, &held) => {
+                strategy::reroll_for(&player.dice, &held)
+            }
+            Some(answer @ Answer::Hold(_)) => {
+                self.illegal(
+                    &answer.to_string(),
+                    "those dice aren't in the roll".to_owned(),
+                );
+                Vec::new()
+            }
+            Some(Answer::Score(category)) => {
+                self.pending = Some(category);
+                Vec::new()
+            }
+            None => Vec::new(),
+        }
+    }
+
+    fn score(&mut self, player: &Player, possible_scores: &[Score]) -> Score {
+        let category = match self.pending.take() {
+            Some(category) => Some(category),
+            None => match self.ask(player, 0) {
+                Some(Answer::Score(category)) => Some(category),
+                Some(answer @ Answer::Hold(_)) => {
+                    self.illegal(&answer.to_string(), "there are no rolls left".to_owned());
+                    None
+                }
+                None => None,
+            },
+        };
+
+        let chosen = category.and_then(|category| {
+            possible_scores
+                .iter()
+                .find(|score| score.category() == Some(category))
+        });
+        match (chosen, category) {
+            (Some(score), _) => *score,
+            (None, category) => {
+                if let Some(category) = category {
+                    let answer = Answer::Score(category).to_string();
+                    self.illegal(&answer, format!("{} can't be scored", category));
+                }
+                Greedy.score(player, possible_scores)
+            }
+        }
+    }
+
+    fn observe(&mut self, players: &[Player], current: usize) {
+        self.players = players.to_vec();
+        self.seat = current;
+    }
+
+    fn faults(&self) -> &[Fault] {
+        &self.faults
+    }
+}
+
+impl Drop for Engine {
+    fn drop(&mut self) {
+        if self.connected {
+            let _ = self.send("quit");
+        }
+        if let Some(child) = self.child.as_mut() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+/// Plays a strategy as an engine, reading requests from `input` and
+/// answering on `output` until told to quit or the input ends.
+///
+/// # Example
+/// ```rust
+/// use yahtzee::engine;
+/// use yahtzee::strategy::Greedy;
+///
+/// let requests = "yahtzee 1\nmove rolls=2 dice=6,6,1,2,6 open=1,2,6,3k,ch me=0/ opponent=12/ch:12\nquit\n";
+/// let mut answers = Vec::new();
+/// engine::serve(&mut Greedy, requests.as_bytes(), &mut answers).unwrap();
+/// assert_eq!(String::from_utf8(answers).unwrap(), "ready\nhold 6 6 6\n");
+///
+/// // positions that can't come up in a game are errors
+/// let full = "me=0/1:0,2:0,3:0,4:0,5:0,6:0,3k:0,4k:0,fh:0,ss:0,ls:0,yz:0,ch:5";
+/// for position in [
+///     "rolls=2 dice=7,7,7,1,2 open=1,2 me=0/",
+///     "rolls=2 dice=6,6,1,2 open=1,2 me=0/",
+///     "rolls=3 dice=6,6,1,2,6 open=1,2 me=0/",
+///     &format!("rolls=0 dice=6,6,1,2,6 open= {}", full),
+/// ] {
+///     let requests = format!("yahtzee 1\nmove {}\n", position);
+///     let error = engine::serve(&mut Greedy, requests.as_bytes(), &mut Vec::new()).unwrap_err();
+///     assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+/// }
+/// ```
+pub fn serve<S, R, W>(strategy: &mut S, input: R, mut output: W) -> io::Result<()>
+where
+    S: Strategy + ?Sized,
+    R: BufRead,
+    W: Write,
+{
+    for line in input.lines() {
+        let line = line?;
+        let answer = match line.split_whitespace().next() {
+            Some("yahtzee") => "ready".to_owned(),
+            Some("move") => match parse_request(&line) {
+                Ok((players, rolls_left)) => decide(strategy, &players, rolls_left).to_string(),
+                Err(error) => {
+                    return Err(io::Error::new(io::ErrorKind::InvalidData, error));
+                }
+            },
+            Some("quit") => return Ok(()),
+            _ => continue,
+        };
+        writeln!(output, "{}", answer)?;
+        output.flush()?;
+    }
+    Ok(())
+}
+
+/// the move a strategy makes for the first of the players
+fn decide<S: Strategy + ?Sized>(strategy: &mut S, players: &[Player], rolls_left: u32) -> Answer {
+    let player = &players[0];
+    strategy.observe(players, 0);
+    if rolls_left > 0 {
+        let reroll = strategy.reroll(player, rolls_left);
+        if !reroll.is_empty() {
+            let held = (1..=player.dice.len() as u8)
+                .filter(|die| !reroll.contains(die))
+                .map(|die| player.dice[die as usize - 1])
+                .collect();
+            return Answer::Hold(held);
+        }
+    }
+    let score = strategy.score(player, &player.possible_scores());
+    Answer::Score(score.category().expect("strategies pick a box"))
+}
+
+/// the line describing a position to an engine
+fn request(player: &Player, opponents: &[&Player], rolls_left: u32) -> String {
+    let dice: Vec<String> = player.dice.iter().map(u32::to_string).collect();
+    let open: Vec<&str> = player
+        .possible_scores()
+        .iter()
+        .filter_map(Score::category)
+        .map(Category::code)
+        .collect();
+    let mut line = format!(
+        "move rolls={} dice={} open={} me={}",
+        rolls_left,
+        dice.join(","),
+        open.join(","),
+        card(player)
+    );
+    for opponent in opponents {
+        line.push_str(&format!(" opponent={}", card(opponent)));
+    }
+    line
+}
+
+/// a player's total and every box they've scored, in the order scored
+fn card(player: &Player) -> String {
+    let scores: Vec<String> = player
+        .scores
+        .iter()
+        .filter_map(|score| Some(format!("{}:{}", score.category()?.code(), score.value())))
+        .collect();
+    format!("{}/{}", player.score, scores.join(","))
+}
+
+/// the players in a request, the engine's own first, and the rolls left
+fn parse_request(line: &str) -> Result<(Vec<Player>, u32), String> {
+    let mut players = vec![Player::new("me".to_owned())];
+    let mut rolls_left = None;
+    let mut dice = None;
+    for field in line.split_whitespace().skip(1) {
+        let (key, value) = field
+            .split_once('=')
+            .ok_or_else(|| format!("\"{}\" is not a field", field))?;
+        match key {
+            "rolls" => {
+                let rolls = value
+                    .parse()
+                    .map_err(|_| format!("{} is not a number of rolls", value))?;
+                rolls_left = Some(rolls);
+            }
+            "dice" => {
+                let values = value
+                    .split(',')
+                    .map(|die| die.parse().map_err(|_| format!("{} is not a die", die)))
+                    .collect::<Result<_, _>>()?;
+                dice = Some(values);
+            }
+            "me" => parse_card(&mut players[0], value)?,
+            "opponent" => {
+                let mut opponent = Player::new(format!("opponent {}", players.len()));
+                parse_card(&mut opponent, value)?;
+                players.push(opponent);
+            }
+            _ => {}
+        }
+    }
+
+    let rolls_left = rolls_left.ok_or("the rolls left are missing")?;
+    if rolls_left >= ROLLS_PER_TURN {
+        return Err(format!("{} rolls left is more than a turn has", rolls_left));
+    }
+    let dice = dice.ok_or("the dice are missing")?;
+    players[0]
+        .set_dice(dice)
+        .map_err(|error| error.to_string())?;
+    if players[0].possible_scores().is_empty() {
+        return Err("there is no box open to score".to_owned());
+    }
+    Ok((players, rolls_left))
+}
+
+/// fills in a player's scores from the scores in a card
+fn parse_card(player: &mut Player, card: &str) -> Result<(), String> {
+    let scores = card.split_once('/').map_or(card, |(_, scores)| scores);
+    for score in scores.split(',').filter(|score| !score.is_empty()) {
+        let (category, points) = score
+            .split_once(':')
+            .ok_or_else(|| format!("\"{}\" is not a score", score))?;
+        let category: Category = category.parse().map_err(|error| format!("{}", error))?;
+        let points = points
+            .parse()
+            .map_err(|_| format!("{} is not a number of points", points))?;
+        let bonus_yahtzee = category == Category::Yahtzee && points == 150;
+        if !bonus_yahtzee && !DiceHand::all().any(|hand| hand.score(category) == points) {
+            return Err(format!("{} can't score {}", category, points));
+        }
+        if !bonus_yahtzee && player.scored(category).is_some() {
+            return Err(format!("{} is scored twice", category));
+        }
+        player.update_score(category.score(points));
+    }
+    Ok(())
+}
+
+/// whether dice with the held values can be kept from a roll
+fn is_held(dice: &[u32], held: &[u32]) -> bool {
+    let mut left = dice.to_vec();
+    held.iter()
+        .all(|value| match left.iter().position(|die| die == value) {
+            Some(position) => {
+                left.swap_remove(position);
+                true
+            }
+            None => false,
+        })
+}
@@ -0,0 +1,455 @@
+use crate::game::{GameError, ROLLS_PER_TURN};
+use crate::player::Player;
+use crate::score::{Category, Score};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::fmt;
+
+/// the number of dice in a game played through the environment
+const NUM_DICE: usize = 5;
+const NUM_FACES: usize = 6;
+
+/// hold masks, one for every subset of the dice
+pub const NUM_HOLDS: usize = 1 << NUM_DICE;
+
+/// the size of the flat action space: every hold mask, then every box
+pub const NUM_ACTIONS: usize = NUM_HOLDS + Category::STANDARD.len();
+
+/// The size of an observation, laid out as
+///
+/// - one-hot faces of each die in order, 30 values
+/// - whether each die was held at the last reroll, 5 values
+/// - one-hot rolls left, 3 values
+/// - whether each box is filled, 13 values
+/// - the points in each box over 50, 13 values
+/// - the upper section total over the 63 needed for the bonus, capped at 1
+/// - the number of bonus Yahtzees scored
+/// - the fraction of turns played
+pub const OBSERVATION_SIZE: usize =
+    NUM_DICE * NUM_FACES + NUM_DICE + ROLLS_PER_TURN as usize + 2 * Category::STANDARD.len() + 3;
+
+pub type Observation = [f32; OBSERVATION_SIZE];
+
+/// the observations, rewards and whether each game finished after a step
+/// of a batch
+pub type BatchStep<'a> = (&'a [f32], &'a [f32], &'a [bool]);
+
+/// One move in the environment
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Action {
+    /// hold the dice whose bits are set, numbering the dice from the lowest
+    /// bit, and reroll the rest
+    Hold(u8),
+    Score(Category),
+}
+
+impl Action {
+    /// The action at an index of the flat action space.
+    ///
+    /// # Example
+    /// ```rust
+    /// use yahtzee::env::{Action, NUM_ACTIONS};
+    /// use yahtzee::score::Category;
+    ///
+    /// assert_eq!(Action::from_index(0), Some(Action::Hold(0)));
+    /// assert_eq!(Action::from_index(31), Some(Action::Hold(0b11111)));
+    /// assert_eq!(Action::from_index(32), Some(Action::Score(Category::Aces)));
+    /// assert_eq!(Action::from_index(44), Some(Action::Score(Category::Yahtzee)));
+    /// assert_eq!(Action::from_index(NUM_ACTIONS), None);
+    /// assert_eq!(Action::Score(Category::Chance).index(), 43);
+    /// ```
+    pub fn from_index(index: usize) -> Option<Action> {
+        match index {
+            _ if index < NUM_HOLDS => Some(Action::Hold(index as u8)),
+            _ => Category::STANDARD
+                .get(index - NUM_HOLDS)
+                .copied()
+                .map(Action::Score),
+        }
+    }
+
+    pub fn index(self) -> usize {
+        match self {
+            Action::Hold(mask) => usize::from(mask) % NUM_HOLDS,
+            Action::Score(category) => NUM_HOLDS + category.index(),
+        }
+    }
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Action::Hold(mask) => write!(f, "hold {:05b}", mask),
+            Action::Score(category) => write!(f, "score {}", category),
+        }
+    }
+}
+
+/// A solo game of regular yahtzee for training agents, in the style of a
+/// Gym environment.
+///
+/// Every score action ends a turn and rolls the dice for the next one, and
+/// its reward is the points it scored, including any bonus it earned. Hold
+/// actions reroll the dice that aren't held and have no reward. The episode
+/// is done once every turn has been played.
+///
+/// # Example
+/// ```rust
+/// use yahtzee::env::{Action, Env, NUM_ACTIONS};
+///
+/// let mut env = Env::new(7);
+/// let mut total = 0.0;
+/// loop {
+///     // score the first box that can be
+///     let legal = env.legal_actions();
+///     let action = (32..NUM_ACTIONS).find(|action| legal[*action]).unwrap();
+///     let (_, reward, done) = env.step(Action::from_index(action).unwrap()).unwrap();
+///     total += reward;
+///     if done {
+///         break;
+///     }
+/// }
+/// assert_eq!(total, env.player().score as f32);
+/// assert!(env.step(Action::Hold(0)).is_err());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Env {
+    player: Player,
+    rng: StdRng,
+    rolls_left: u32,
+    /// the dice held at the last reroll this turn
+    held: u8,
+    turns_played: usize,
+}
+
+impl Env {
+    /// an environment ready to play, seeded for a repeatable game
+    pub fn new(seed: u64) -> Env {
+        let mut env = Env {
+            player: Player::new("agent".to_owned()),
+            rng: StdRng::seed_from_u64(seed),
+            rolls_left: 0,
+            held: 0,
+            turns_played: 0,
+        };
+        env.new_game();
+        env
+    }
+
+    /// starts a new game from a seed, returning the first observation
+    pub fn reset(&mut self, seed: u64) -> Observation {
+        self.rng = StdRng::seed_from_u64(seed);
+        self.new_game();
+        self.observation()
+    }
+
+    /// starts a new game carrying on with the random number generator
+    fn new_game(&mut self) {
+        self.player = Player::new("agent".to_owned());
+        self.turns_played = 0;
+        self.new_turn();
+    }
+
+    fn new_turn(&mut self) {
+        self.player.roll_dice_with(&mut self.rng);
+        self.rolls_left = ROLLS_PER_TURN - 1;
+        self.held = 0;
+    }
+
+    /// the player, with the dice and scorecard
+    pub fn player(&self) -> &Player {
+        &self.player
+    }
+
+    pub fn rolls_left(&self) -> u32 {
+        self.rolls_left
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.turns_played == self.player.rules.num_rounds()
+    }
+
+    /// Plays an action, returning the observation after it, its reward and
+    /// whether the game is over.
+    ///
+    /// # Example
+    /// ```rust
+    /// use yahtzee::env::{Action, Env};
+    /// use yahtzee::game::GameError;
+    /// use yahtzee::score::Category;
+    ///
+    /// let mut env = Env::new(1);
+    /// let dice = env.player().dice.clone();
+    /// env.step(Action::Hold(0b00011)).unwrap();
+    /// assert_eq!(env.player().dice[..2], dice[..2]);
+    /// env.step(Action::Hold(0b11111)).unwrap();
+    /// assert_eq!(env.step(Action::Hold(0)), Err(GameError::NoRollsLeft));
+    ///
+    /// let (_, reward, done) = env.step(Action::Score(Category::Chance)).unwrap();
+    /// assert!(reward >= 5.0 && !done);
+    /// assert_eq!(env.rolls_left(), 2);
+    /// assert_eq!(env.step(Action::Score(Category::Chance)), Err(GameError::AlreadyScored(Category::Chance)));
+    /// ```
+    pub fn step(&mut self, action: Action) -> Result<(Observation, f32, bool), GameError> {
+        let reward = self.play(action)?;
+        Ok((self.observation(), reward, self.is_done()))
+    }
+
+    /// plays an action, returning its reward
+    fn play(&mut self, action: Action) -> Result<f32, GameError> {
+        if self.is_done() {
+            return Err(GameError::GameOver);
+        }
+        match action {
+            Action::Hold(_) if self.rolls_left == 0 => Err(GameError::NoRollsLeft),
+            Action::Hold(mask) => {
+                let reroll = (0..NUM_DICE as u8)
+                    .filter(|die| mask & (1 << die) == 0)
+                    .map(|die| die + 1)
+                    .collect();
+                self.player.reroll_with(reroll, &mut self.rng);
+                self.rolls_left -= 1;
+                self.held = mask % NUM_HOLDS as u8;
+                Ok(0.0)
+            }
+            Action::Score(category) => {
+                let score = match self.player.possible_score(category) {
+                    Some(score) => score,
+                    None => return Err(GameError::AlreadyScored(category)),
+                };
+                let before = self.player.score;
+                self.player.update_score(score);
+                self.turns_played += 1;
+                if !self.is_done() {
+                    self.new_turn();
+                }
+                Ok((self.player.score - before) as f32)
+            }
+        }
+    }
+
+    /// Which actions can be played, indexed by the flat action space.
+    ///
+    /// # Example
+    /// ```rust
+    /// use yahtzee::env::{Action, Env, NUM_HOLDS};
+    /// use yahtzee::score::Category;
+    ///
+    /// let mut env = Env::new(2);
+    /// assert!(env.legal_actions().iter().all(|legal| *legal));
+    /// env.step(Action::Score(Category::Fours)).unwrap();
+    /// let legal = env.legal_actions();
+    /// assert!(!legal[Action::Score(Category::Fours).index()]);
+    /// assert_eq!(legal.iter().filter(|legal| **legal).count(), NUM_HOLDS + 12);
+    /// ```
+    pub fn legal_actions(&self) -> [bool; NUM_ACTIONS] {
+        let mut legal = [false; NUM_ACTIONS];
+        self.legal_actions_into(&mut legal);
+        legal
+    }
+
+    /// writes which actions can be played into the start of a buffer
+    pub fn legal_actions_into(&self, legal: &mut [bool]) {
+        let legal = &mut legal[..NUM_ACTIONS];
+        legal.iter_mut().for_each(|legal| *legal = false);
+        if self.is_done() {
+            return;
+        }
+        if self.rolls_left > 0 {
+            legal[..NUM_HOLDS]
+                .iter_mut()
+                .for_each(|legal| *legal = true);
+        }
+        for score in self.player.possible_scores() {
+            if let Some(category) = score.category() {
+                legal[Action::Score(category).index()] = true;
+            }
+        }
+    }
+
+    pub fn observation(&self) -> Observation {
+        let mut observation = [0.0; OBSERVATION_SIZE];
+        self.observe_into(&mut observation);
+        observation
+    }
+
+    /// writes the observation into the start of a buffer
+    ///
+    /// # Example
+    /// ```rust
+    /// use yahtzee::env::{Env, OBSERVATION_SIZE};
+    ///
+    /// let env = Env::new(3);
+    /// let observation = env.observation();
+    /// // one face for each die, and two rolls left
+    /// assert_eq!(observation[..30].iter().sum::<f32>(), 5.0);
+    /// assert_eq!(observation[35..38], [0.0, 0.0, 1.0]);
+    /// assert!(observation[38..].iter().all(|value| *value == 0.0));
+    /// assert_eq!(observation.len(), OBSERVATION_SIZE);
+    /// ```
+    pub fn observe_into(&self, observation: &mut [f32]) {
+        let observation = &mut observation[..OBSERVATION_SIZE];
+        observation.iter_mut().for_each(|value| *value = 0.0);
+        let (dice, rest) = observation.split_at_mut(NUM_DICE * NUM_FACES);
+        let (held, rest) = rest.split_at_mut(NUM_DICE);
+        let (rolls, rest) = rest.split_at_mut(ROLLS_PER_TURN as usize);
+        let (filled, rest) = rest.split_at_mut(Category::STANDARD.len());
+        let (points, rest) = rest.split_at_mut(Category::STANDARD.len());
+
+        for (die, value) in self.player.dice.iter().enumerate() {
+            dice[die * NUM_FACES + *value as usize - 1] = 1.0;
+        }
+        for (die, held) in held.iter_mut().enumerate() {
+            if self.held & (1 << die) != 0 {
+                *held = 1.0;
+            }
+        }
+        rolls[self.rolls_left as usize] = 1.0;
+        for score in self.player.scores.iter() {
+            match (score, score.category()) {
+                (Score::Yahtzee(150), _) => rest[1] += 1.0,
+                (_, Some(category)) => {
+                    filled[category.index()] = 1.0;
+                    points[category.index()] = score.value() as f32 / 50.0;
+                }
+                (_, None) => {}
+            }
+        }
+        let threshold = self.player.rules.upper_bonus_threshold() as f32;
+        rest[0] = (self.player.upper_total() as f32 / threshold).min(1.0);
+        rest[2] = self.turns_played as f32 / self.player.rules.num_rounds() as f32;
+    }
+}
+
+/// Many environments stepped together, with flat buffers of observations,
+/// rewards and legal actions for feeding straight into a model.
+///
+/// Each environment starts a new game as soon as one is done, so the
+/// observation after a finished game is the first of the next.
+///
+/// # Example
+/// ```rust
+/// use yahtzee::env::{Action, BatchEnv, NUM_ACTIONS, OBSERVATION_SIZE};
+///
+/// let mut batch = BatchEnv::new(4);
+/// assert_eq!(batch.reset(10).len(), 4 * OBSERVATION_SIZE);
+/// let mut finished = 0;
+/// for _ in 0..13 {
+///     // everyone scores the last box they can
+///     let actions: Vec<Action> = batch
+///         .legal_actions()
+///         .chunks(NUM_ACTIONS)
+///         .map(|legal| Action::from_index((0..NUM_ACTIONS).rev().find(|action| legal[*action]).unwrap()).unwrap())
+///         .collect();
+///     let (_, rewards, dones) = batch.step(&actions).unwrap();
+///     assert_eq!(rewards.len(), 4);
+///     finished += dones.iter().filter(|done| **done).count();
+/// }
+/// assert_eq!(finished, 4);
+/// ```
+#[derive(Debug, Clone)]
+pub struct BatchEnv {
+    envs: Vec<Env>,
+    observations: Vec<f32>,
+    rewards: Vec<f32>,
+    dones: Vec<bool>,
+    legal: Vec<bool>,
+}
+
+/// An action that couldn't be played by one of a batch of environments
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct BatchError {
+    /// the environment the action was for
+    pub index: usize,
+    pub error: GameError,
+}
+
+impl fmt::Display for BatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "environment {}: {}", self.index, self.error)
+    }
+}
+
+impl BatchEnv {
+    pub fn new(size: usize) -> BatchEnv {
+        BatchEnv {
+            envs: (0..size as u64).map(Env::new).collect(),
+            observations: vec![0.0; size * OBSERVATION_SIZE],
+            rewards: vec![0.0; size],
+            dones: vec![false; size],
+            legal: vec![false; size * NUM_ACTIONS],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.envs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.envs.is_empty()
+    }
+
+    pub fn envs(&self) -> &[Env] {
+        &self.envs
+    }
+
+    /// starts new games in every environment, each seeded from the seed and
+    /// its index, returning the observations
+    pub fn reset(&mut self, seed: u64) -> &[f32] {
+        for (index, env) in self.envs.iter_mut().enumerate() {
+            env.reset(seed.wrapping_add(index as u64));
+        }
+        self.observe();
+        &self.observations
+    }
+
+    /// Plays an action in each environment, returning the observations,
+    /// rewards and whether each game finished. Nothing is played if any
+    /// action can't be.
+    pub fn step(&mut self, actions: &[Action]) -> Result<BatchStep<'_>, BatchError> {
+        assert_eq!(
+            actions.len(),
+            self.envs.len(),
+            "one action is needed for each environment"
+        );
+        for (index, (env, action)) in self.envs.iter().zip(actions).enumerate() {
+            let legal = match action {
+                _ if env.is_done() => Err(GameError::GameOver),
+                Action::Hold(_) if env.rolls_left == 0 => Err(GameError::NoRollsLeft),
+                Action::Score(category) if env.player.possible_score(*category).is_none() => {
+                    Err(GameError::AlreadyScored(*category))
+                }
+                _ => Ok(()),
+            };
+            legal.map_err(|error| BatchError { index, error })?;
+        }
+
+        for (index, (env, action)) in self.envs.iter_mut().zip(actions).enumerate() {
+            self.rewards[index] = env.play(*action).expect("the actions were checked");
+            self.dones[index] = env.is_done();
+            if env.is_done() {
+                env.new_game();
+            }
+        }
+        self.observe();
+        Ok((&self.observations, &self.rewards, &self.dones))
+    }
+
+    /// which actions each environment can play, `NUM_ACTIONS` at a time
+    pub fn legal_actions(&mut self) -> &[bool] {
+        for (env, legal) in self.envs.iter().zip(self.legal.chunks_mut(NUM_ACTIONS)) {
+            env.legal_actions_into(legal);
+        }
+        &self.legal
+    }
+
+    fn observe(&mut self) {
+        for (env, observation) in self
+            .envs
+            .iter()
+            .zip(self.observations.chunks_mut(OBSERVATION_SIZE))
+        {
+            env.observe_into(observation);
+        }
+    }
+}
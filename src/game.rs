@@ -0,0 +1,825 @@
+use crate::clock::{Clock, TimeControl};
+use crate::daily::{self, Date};
+use crate::match_play::Match;
+use crate::player::{DiceError, Player};
+use crate::rules::Rules;
+use crate::score::{Category, Score};
+use crate::standings::{self, Standing, TieBreak};
+use crate::storage;
+use crate::strategy::Bot;
+use crate::team::{self, Team, TeamError};
+use crate::transcript::{Decision, Move, Transcript};
+use rand::distributions::{Distribution, Uniform};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::Duration;
+use std::{fmt, io};
+
+/// number of times the dice can be rolled in a turn
+pub const ROLLS_PER_TURN: u32 = 3;
+
+/// file in the data directory a game is saved to when no path is given
+pub const SAVE_FILE: &str = "saved_game.json";
+
+/// The state of a game in progress: every player's scorecard, whose turn it
+/// is and how far into their turn they are.
+///
+/// # Example
+/// ```rust
+/// use rand::rngs::StdRng;
+/// use rand::SeedableRng;
+/// use yahtzee::game::Game;
+/// use yahtzee::rules::Rules;
+/// use yahtzee::score::Category;
+///
+/// let mut rng = StdRng::seed_from_u64(3);
+/// let mut game = Game::new(Rules::default(), vec!["Ann".to_owned(), "Bob".to_owned()]);
+/// game.roll(&mut rng).unwrap();
+/// assert_eq!(game.rolls_left(), 2);
+/// game.reroll(vec![1, 2], &mut rng).unwrap();
+/// game.score(Category::Chance).unwrap();
+/// assert_eq!(game.current_player().name, "Bob");
+/// assert_eq!(game.rolls_left(), 3);
+/// ```
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct Game {
+    pub rules: Rules,
+    pub players: Vec<Player>,
+    /// the round being played, starting from 1
+    pub round: usize,
+    /// index of the player whose turn it is
+    pub current: usize,
+    /// how many times the dice have been rolled this turn
+    pub rolls: u32,
+    /// whether any number of moves can be taken back, rather than only the
+    /// last score before the next roll
+    #[serde(default)]
+    pub practice: bool,
+    /// every hold and score so far, less any that were taken back
+    #[serde(default)]
+    pub moves: Vec<Move>,
+    /// the day of the daily challenge being played, whose dice come from the
+    /// date instead of the random number generator
+    #[serde(default)]
+    pub daily: Option<Date>,
+    /// the computer player in each seat, if any, with seats past the end
+    /// played by people
+    #[serde(default)]
+    pub bots: Vec<Option<Bot>>,
+    /// the final score the players are aiming for, which hints show the
+    /// chance of reaching
+    #[serde(default)]
+    pub target: Option<u32>,
+    /// the teams playing, if any. Seat `n` plays for team `n` modulo the
+    /// number of teams.
+    #[serde(default)]
+    pub teams: Vec<Team>,
+    /// whether each team plays one scorecard, its members taking turns
+    #[serde(default)]
+    pub shared_cards: bool,
+    /// how players finishing level are separated
+    #[serde(default)]
+    pub tie_break: TieBreak,
+    /// how long players have to play, if the game is timed
+    #[serde(default)]
+    pub time_control: Option<TimeControl>,
+    /// whether the dice are rolled outside the game and entered by hand
+    #[serde(default)]
+    pub real_dice: bool,
+    /// the match the game is part of, with the games played before it
+    #[serde(default)]
+    pub series: Option<Match>,
+    /// each player's time spent on their finished turns
+    #[serde(default)]
+    time_used: Vec<Duration>,
+    /// the turn being timed and when it started
+    #[serde(skip)]
+    turn_clock: Option<TurnClock>,
+    #[serde(default)]
+    undo: Vec<Snapshot>,
+    #[serde(default)]
+    redo: Vec<Snapshot>,
+    /// what every die came up as on each roll of each turn, so rolling again
+    /// after an undo gives the same dice
+    #[serde(default)]
+    outcomes: Vec<Vec<Vec<u32>>>,
+}
+
+/// When the turn being timed started
+#[derive(Debug, PartialEq, Clone, Copy)]
+struct TurnClock {
+    player: usize,
+    round: usize,
+    started: Duration,
+}
+
+/// The parts of a game put back by an undo
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+struct Snapshot {
+    players: Vec<Player>,
+    #[serde(default)]
+    moves: Vec<Move>,
+    round: usize,
+    current: usize,
+    rolls: u32,
+}
+
+/// Reasons a move can't be made
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum GameError {
+    GameOver,
+    NotRolled,
+    AlreadyRolled,
+    NoRollsLeft,
+    DieOutOfRange(u8),
+    NotInDice(u32),
+    AlreadyScored(Category),
+    NotOnScorecard(Category),
+    NothingToUndo,
+    NothingToRedo,
+    Dice(DiceError),
+}
+
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameError::GameOver => write!(f, "the game is over"),
+            GameError::NotRolled => write!(f, "the dice haven't been rolled yet"),
+            GameError::AlreadyRolled => write!(f, "the dice have already been rolled"),
+            GameError::NoRollsLeft => write!(f, "there are no rolls left this turn"),
+            GameError::DieOutOfRange(die) => write!(f, "there is no die {}", die),
+            GameError::NotInDice(value) => write!(f, "there is no {} to keep", value),
+            GameError::AlreadyScored(category) => write!(f, "{} has already been scored", category),
+            GameError::NotOnScorecard(category) => {
+                write!(f, "{} is not on this scorecard", category)
+            }
+            GameError::NothingToUndo => write!(f, "there is nothing to undo"),
+            GameError::NothingToRedo => write!(f, "there is nothing to redo"),
+            GameError::Dice(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<DiceError> for GameError {
+    fn from(error: DiceError) -> GameError {
+        GameError::Dice(error)
+    }
+}
+
+impl Game {
+    pub fn new(rules: Rules, names: Vec<String>) -> Game {
+        Game {
+            rules,
+            players: names
+                .into_iter()
+                .map(|name| Player::with_rules(name, rules))
+                .collect(),
+            round: 1,
+            current: 0,
+            rolls: 0,
+            practice: false,
+            moves: Vec::new(),
+            daily: None,
+            bots: Vec::new(),
+            target: None,
+            teams: Vec::new(),
+            shared_cards: false,
+            tie_break: TieBreak::default(),
+            time_control: None,
+            real_dice: false,
+            series: None,
+            time_used: Vec::new(),
+            turn_clock: None,
+            undo: Vec::new(),
+            redo: Vec::new(),
+            outcomes: Vec::new(),
+        }
+    }
+
+    /// Starts a game between teams, either with a scorecard for every player
+    /// and turns going around the teams, or with a scorecard for each team
+    /// and its members taking turns on it.
+    ///
+    /// # Example
+    /// ```rust
+    /// use yahtzee::game::Game;
+    /// use yahtzee::rules::Rules;
+    /// use yahtzee::team::Team;
+    ///
+    /// let team = |name: &str, members: &[&str]| Team {
+    ///     name: name.to_owned(),
+    ///     members: members.iter().map(|member| member.to_string()).collect(),
+    /// };
+    /// let teams = vec![team("Red", &["Ann", "Bob"]), team("Blue", &["Cat", "Dan"])];
+    ///
+    /// let game = Game::with_teams(Rules::default(), teams.clone(), false).unwrap();
+    /// assert_eq!(game.players.len(), 4);
+    /// assert_eq!(game.team_of(2), Some(0));
+    /// let red: Vec<&str> = game.team_players(0).map(|player| player.name.as_str()).collect();
+    /// assert_eq!(red, vec!["Ann", "Bob"]);
+    ///
+    /// let shared = Game::with_teams(Rules::default(), teams, true).unwrap();
+    /// assert_eq!(shared.players.len(), 2);
+    /// assert_eq!(shared.current_player().name, "Red");
+    /// assert_eq!(shared.turn_taker(), "Ann");
+    /// ```
+    pub fn with_teams(
+        rules: Rules,
+        teams: Vec<Team>,
+        shared_cards: bool,
+    ) -> Result<Game, TeamError> {
+        let names = match shared_cards {
+            true => {
+                team::check(&teams)?;
+                teams.iter().map(|team| team.name.clone()).collect()
+            }
+            false => team::seating(&teams)?,
+        };
+        let mut game = Game::new(rules, names);
+        game.teams = teams;
+        game.shared_cards = shared_cards;
+        Ok(game)
+    }
+
+    /// the team a player's scorecard counts for, if they're playing in teams
+    pub fn team_of(&self, player: usize) -> Option<usize> {
+        match self.teams.len() {
+            0 => None,
+            teams => Some(player % teams),
+        }
+    }
+
+    /// the scorecards that count for a team
+    pub fn team_players(&self, team: usize) -> impl Iterator<Item = &Player> {
+        self.players
+            .iter()
+            .skip(team)
+            .step_by(self.teams.len().max(1))
+    }
+
+    /// a team's total across its scorecards
+    pub fn team_score(&self, team: usize) -> u32 {
+        self.team_players(team).map(|player| player.score).sum()
+    }
+
+    /// the teams ranked by their totals
+    pub fn team_standings(&self, tie_break: TieBreak) -> Vec<Standing> {
+        let teams: Vec<(String, Vec<&Player>)> = self
+            .teams
+            .iter()
+            .enumerate()
+            .map(|(i, team)| (team.name.clone(), self.team_players(i).collect()))
+            .collect();
+        standings::team_standings(&teams, tie_break)
+    }
+
+    /// The name of the person taking the current turn. On a shared scorecard
+    /// the team's members take a round each in turn.
+    pub fn turn_taker(&self) -> &str {
+        match self.teams.get(self.current) {
+            Some(team) if self.shared_cards => &team.members[(self.round - 1) % team.members.len()],
+            _ => &self.current_player().name,
+        }
+    }
+
+    /// the bot playing for a player, or None if a person is
+    pub fn bot(&self, player: usize) -> Option<&Bot> {
+        self.bots.get(player).and_then(Option::as_ref)
+    }
+
+    pub fn current_player(&self) -> &Player {
+        &self.players[self.current]
+    }
+
+    pub fn current_player_mut(&mut self) -> &mut Player {
+        &mut self.players[self.current]
+    }
+
+    /// the number of rounds in the game
+    pub fn num_rounds(&self) -> usize {
+        self.rules.num_rounds()
+    }
+
+    /// whether every player has played every round
+    pub fn is_over(&self) -> bool {
+        self.players.is_empty() || self.round > self.num_rounds()
+    }
+
+    pub fn rolls_left(&self) -> u32 {
+        ROLLS_PER_TURN - self.rolls
+    }
+
+    /// first roll of the current player's turn
+    pub fn roll<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Result<(), GameError> {
+        self.check_playing()?;
+        if self.rolls > 0 {
+            return Err(GameError::AlreadyRolled);
+        }
+        self.clear_history();
+
+        let outcome = self.outcome(rng);
+        self.current_player_mut().dice = outcome;
+        self.rolls = 1;
+        Ok(())
+    }
+
+    /// rerolls the dice at the passed in positions, numbered from 1
+    pub fn reroll<R: Rng + ?Sized>(&mut self, dice: Vec<u8>, rng: &mut R) -> Result<(), GameError> {
+        self.check_can_reroll()?;
+        let num_dice = self.current_player().dice.len();
+        if let Some(die) = dice
+            .iter()
+            .find(|die| **die < 1 || **die as usize > num_dice)
+        {
+            return Err(GameError::DieOutOfRange(*die));
+        }
+
+        if self.practice {
+            self.record();
+        } else {
+            self.redo.clear();
+        }
+        let player = self.current_player();
+        let mut held: Vec<u32> = (1..=num_dice as u8)
+            .filter(|die| !dice.contains(die))
+            .map(|die| player.dice[die as usize - 1])
+            .collect();
+        held.sort_unstable();
+        self.push_move(Decision::Hold(held));
+
+        // the rerolled dice take the roll's values in order, so the values
+        // rolled don't depend on which dice were picked up
+        let mut dice = dice;
+        dice.sort_unstable();
+        dice.dedup();
+        let outcome = self.outcome(rng);
+        let player = self.current_player_mut();
+        for (die, value) in dice.into_iter().zip(outcome) {
+            player.dice[die as usize - 1] = value;
+        }
+        self.rolls += 1;
+        Ok(())
+    }
+
+    /// keeps dice showing the passed in values and rerolls the rest
+    ///
+    /// # Example
+    /// ```rust
+    /// use rand::rngs::StdRng;
+    /// use rand::SeedableRng;
+    /// use yahtzee::game::{Game, GameError};
+    /// use yahtzee::rules::Rules;
+    ///
+    /// let mut rng = StdRng::seed_from_u64(3);
+    /// let mut game = Game::new(Rules::default(), vec!["Ann".to_owned()]);
+    /// game.roll(&mut rng).unwrap();
+    /// game.players[0].dice = vec![5, 2, 5, 1, 3];
+    /// assert_eq!(game.keep(&[5, 5, 5], &mut rng), Err(GameError::NotInDice(5)));
+    /// game.keep(&[5, 5], &mut rng).unwrap();
+    /// assert_eq!(game.players[0].dice.iter().filter(|die| **die == 5).count() >= 2, true);
+    /// ```
+    pub fn keep<R: Rng + ?Sized>(&mut self, values: &[u32], rng: &mut R) -> Result<(), GameError> {
+        self.check_can_reroll()?;
+        let dice = &self.current_player().dice;
+        let mut kept = vec![false; dice.len()];
+        for value in values {
+            match (0..dice.len()).find(|i| !kept[*i] && dice[*i] == *value) {
+                Some(i) => kept[i] = true,
+                None => return Err(GameError::NotInDice(*value)),
+            }
+        }
+
+        let reroll = (1..=dice.len() as u8)
+            .filter(|die| !kept[*die as usize - 1])
+            .collect();
+        self.reroll(reroll, rng)
+    }
+
+    /// sets the dice to values rolled outside of the game, using up the turn's rolls
+    pub fn set_dice(&mut self, dice: Vec<u32>) -> Result<(), GameError> {
+        self.check_playing()?;
+        self.current_player_mut().set_dice(dice)?;
+        self.clear_history();
+        self.rolls = ROLLS_PER_TURN;
+        Ok(())
+    }
+
+    /// scores the dice in a category and moves on to the next turn
+    pub fn score(&mut self, category: Category) -> Result<Score, GameError> {
+        self.check_playing()?;
+        if self.rolls == 0 {
+            return Err(GameError::NotRolled);
+        }
+
+        let player = self.current_player();
+        let score = match player.possible_score(category) {
+            Some(score) => score,
+            None if player.scored(category).is_some() => {
+                return Err(GameError::AlreadyScored(category))
+            }
+            None => return Err(GameError::NotOnScorecard(category)),
+        };
+        self.record();
+        self.push_move(Decision::Score(category));
+        self.current_player_mut().update_score(score);
+
+        self.rolls = 0;
+        self.current += 1;
+        if self.current == self.players.len() {
+            self.current = 0;
+            self.round += 1;
+        }
+        Ok(score)
+    }
+
+    /// Starts timing the current turn, unless it's already being timed,
+    /// first stopping the clock on any other turn.
+    pub fn start_clock<C: Clock + ?Sized>(&mut self, clock: &C) {
+        if self.time_control.is_none() || self.is_over() {
+            return;
+        }
+        match self.turn_clock {
+            Some(turn) if (turn.player, turn.round) == (self.current, self.round) => {}
+            _ => {
+                self.stop_clock(clock);
+                self.turn_clock = Some(TurnClock {
+                    player: self.current,
+                    round: self.round,
+                    started: clock.now(),
+                });
+            }
+        }
+    }
+
+    /// charges the time taken on the turn being timed to its player
+    pub fn stop_clock<C: Clock + ?Sized>(&mut self, clock: &C) {
+        if let Some(turn) = self.turn_clock.take() {
+            if self.time_used.len() <= turn.player {
+                self.time_used.resize(self.players.len(), Duration::ZERO);
+            }
+            self.time_used[turn.player] += clock.now().saturating_sub(turn.started);
+        }
+    }
+
+    /// how long the current player has left to finish their turn, or None
+    /// if the game isn't timed
+    pub fn time_left<C: Clock + ?Sized>(&self, clock: &C) -> Option<Duration> {
+        let control = self.time_control?;
+        let elapsed = match self.turn_clock {
+            Some(turn) if (turn.player, turn.round) == (self.current, self.round) => {
+                clock.now().saturating_sub(turn.started)
+            }
+            _ => Duration::ZERO,
+        };
+        let used = self
+            .time_used
+            .get(self.current)
+            .copied()
+            .unwrap_or_default();
+        Some(control.time_left(used, elapsed))
+    }
+
+    /// Ends the current turn if its player is out of time, rolling for them
+    /// if they hadn't and scoring the time control's fallback, which can't
+    /// be undone.
+    ///
+    /// # Example
+    /// ```rust
+    /// use rand::rngs::StdRng;
+    /// use rand::SeedableRng;
+    /// use std::time::Duration;
+    /// use yahtzee::clock::{FakeClock, Fallback, TimeControl};
+    /// use yahtzee::game::Game;
+    /// use yahtzee::rules::Rules;
+    ///
+    /// let mut rng = StdRng::seed_from_u64(3);
+    /// let clock = FakeClock::default();
+    /// let mut game = Game::new(Rules::default(), vec!["Ann".to_owned(), "Bob".to_owned()]);
+    /// game.time_control = Some(TimeControl {
+    ///     per_turn: Some(Duration::from_secs(10)),
+    ///     per_game: Some(Duration::from_secs(15)),
+    ///     fallback: Fallback::Lowest,
+    /// });
+    ///
+    /// game.start_clock(&clock);
+    /// clock.advance(Duration::from_secs(8));
+    /// assert_eq!(game.time_left(&clock), Some(Duration::from_secs(2)));
+    /// assert_eq!(game.check_clock(&clock, &mut rng), Ok(None));
+    ///
+    /// clock.advance(Duration::from_secs(2));
+    /// let score = game.check_clock(&clock, &mut rng).unwrap().unwrap();
+    /// assert_eq!(score.value(), 0);
+    /// assert_eq!(game.current_player().name, "Bob");
+    /// assert!(!game.can_undo());
+    ///
+    /// // Bob starts with the whole turn, and Ann with what's left of her game
+    /// game.start_clock(&clock);
+    /// assert_eq!(game.time_left(&clock), Some(Duration::from_secs(10)));
+    /// clock.advance(Duration::from_secs(1));
+    /// game.roll(&mut rng).unwrap();
+    /// game.score(yahtzee::score::Category::Chance).unwrap();
+    /// game.start_clock(&clock);
+    /// assert_eq!(game.time_left(&clock), Some(Duration::from_secs(5)));
+    /// ```
+    pub fn check_clock<C: Clock + ?Sized, R: Rng + ?Sized>(
+        &mut self,
+        clock: &C,
+        rng: &mut R,
+    ) -> Result<Option<Score>, GameError> {
+        match (self.time_control, self.time_left(clock)) {
+            (Some(control), Some(left)) if left.is_zero() && !self.is_over() => {
+                if self.rolls == 0 {
+                    self.roll(rng)?;
+                }
+                let category = control
+                    .fallback
+                    .category(self.current_player())
+                    .ok_or(GameError::GameOver)?;
+                let score = self.score(category)?;
+                self.stop_clock(clock);
+                // a score forced by the clock can't be taken back
+                self.undo.clear();
+                self.redo.clear();
+                Ok(Some(score))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// whether there's a move that can be taken back
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    /// Whether a person can take back the last move, which they can't when a
+    /// bot made it since the bot would only play it again.
+    ///
+    /// # Example
+    /// ```rust
+    /// use rand::rngs::StdRng;
+    /// use rand::SeedableRng;
+    /// use yahtzee::game::Game;
+    /// use yahtzee::rules::Rules;
+    /// use yahtzee::score::Category;
+    /// use yahtzee::strategy::Bot;
+    ///
+    /// let mut rng = StdRng::seed_from_u64(3);
+    /// let mut game = Game::new(Rules::default(), vec!["Ann".to_owned(), "Bot".to_owned()]);
+    /// game.bots = vec![None, Some(Bot::Greedy)];
+    /// game.roll(&mut rng).unwrap();
+    /// game.score(Category::Chance).unwrap();
+    /// assert!(game.can_take_back());
+    ///
+    /// game.roll(&mut rng).unwrap();
+    /// game.score(Category::Chance).unwrap();
+    /// assert!(game.can_undo());
+    /// assert!(!game.can_take_back());
+    /// ```
+    pub fn can_take_back(&self) -> bool {
+        self.can_undo()
+            && self
+                .moves
+                .last()
+                .is_some_and(|played| self.bot(played.player).is_none())
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Takes back the last move.
+    ///
+    /// A score can be taken back until the next player rolls. In practice
+    /// games every score and reroll can be taken back. Dice rolled again after
+    /// an undo come up the same as they did the first time.
+    ///
+    /// # Example
+    /// ```rust
+    /// use rand::rngs::StdRng;
+    /// use rand::SeedableRng;
+    /// use yahtzee::game::{Game, GameError};
+    /// use yahtzee::rules::Rules;
+    /// use yahtzee::score::Category;
+    ///
+    /// let mut rng = StdRng::seed_from_u64(3);
+    /// let mut game = Game::new(Rules::default(), vec!["Ann".to_owned(), "Bob".to_owned()]);
+    /// game.roll(&mut rng).unwrap();
+    /// let dice = game.current_player().dice.clone();
+    /// game.score(Category::Aces).unwrap();
+    /// game.undo().unwrap();
+    /// assert_eq!(game.current_player().name, "Ann");
+    /// assert_eq!(game.current_player().dice, dice);
+    /// assert_eq!(game.current_player().score, 0);
+    ///
+    /// game.redo().unwrap();
+    /// game.roll(&mut rng).unwrap();
+    /// assert_eq!(game.undo(), Err(GameError::NothingToUndo));
+    ///
+    /// let mut practice = Game::new(Rules::default(), vec!["Ann".to_owned()]);
+    /// practice.practice = true;
+    /// practice.roll(&mut rng).unwrap();
+    /// practice.reroll(vec![1, 2, 3, 4, 5], &mut rng).unwrap();
+    /// let rerolled = practice.current_player().dice.clone();
+    /// practice.undo().unwrap();
+    /// practice.reroll(vec![1, 2, 3, 4, 5], &mut rng).unwrap();
+    /// assert_eq!(practice.current_player().dice, rerolled);
+    /// ```
+    pub fn undo(&mut self) -> Result<(), GameError> {
+        let snapshot = self.undo.pop().ok_or(GameError::NothingToUndo)?;
+        self.redo.push(self.snapshot());
+        self.restore(snapshot);
+        Ok(())
+    }
+
+    /// puts back the last move that was taken back
+    pub fn redo(&mut self) -> Result<(), GameError> {
+        let snapshot = self.redo.pop().ok_or(GameError::NothingToRedo)?;
+        self.undo.push(self.snapshot());
+        self.restore(snapshot);
+        Ok(())
+    }
+
+    /// adds the end of game bonuses to every player
+    pub fn finish(&mut self) {
+        for player in self.players.iter_mut() {
+            player.endgame();
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        storage::save_json(path, self)
+    }
+
+    /// Loads a saved game, checking it's one that can be played on.
+    ///
+    /// # Example
+    /// ```rust
+    /// use std::{env, fs, io};
+    /// use yahtzee::game::Game;
+    /// use yahtzee::rules::Rules;
+    ///
+    /// let path = env::temp_dir().join(format!("yahtzee-game-{}.json", std::process::id()));
+    /// let mut game = Game::new(Rules::default(), vec!["Ann".to_owned()]);
+    /// game.save(&path).unwrap();
+    /// assert_eq!(Game::load(&path).unwrap(), game);
+    ///
+    /// game.current = 1;
+    /// game.save(&path).unwrap();
+    /// let error = Game::load(&path).unwrap_err();
+    /// assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    /// fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn load(path: &Path) -> io::Result<Game> {
+        let game = storage::load_json::<Option<Game>>(path)?.ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("no saved game at {}", path.display()),
+            )
+        })?;
+        game.check()
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+        Ok(game)
+    }
+
+    /// why a loaded game can't be played on, if it can't
+    fn check(&self) -> Result<(), String> {
+        let rules = Rules::new(self.rules.num_dice, self.rules.num_faces)
+            .map_err(|error| error.to_string())?;
+        let fits = |dice: &[u32]| {
+            dice.len() == rules.num_dice && dice.iter().all(|die| *die <= rules.num_faces)
+        };
+        if self.current >= self.players.len() {
+            return Err(format!(
+                "player {} is to play but there are {} players",
+                self.current + 1,
+                self.players.len()
+            ));
+        }
+        if self.round == 0 {
+            return Err("the rounds start from 1".to_owned());
+        }
+        if self.rolls > ROLLS_PER_TURN {
+            return Err(format!("the dice were rolled {} times", self.rolls));
+        }
+        for player in &self.players {
+            if player.rules != rules || !fits(&player.dice) {
+                return Err(format!("{}'s dice don't fit the rules", player.name));
+            }
+        }
+        if self.bots.len() > self.players.len() {
+            return Err(format!(
+                "there are {} bots for {} players",
+                self.bots.len(),
+                self.players.len()
+            ));
+        }
+        let turns = self.num_rounds() * self.players.len();
+        let rolled = |rolls: &Vec<Vec<u32>>| {
+            rolls.len() <= ROLLS_PER_TURN as usize
+                && rolls.iter().all(|dice| fits(dice) && !dice.contains(&0))
+        };
+        if self.outcomes.len() > turns || !self.outcomes.iter().all(rolled) {
+            return Err("the rolled dice don't fit the game".to_owned());
+        }
+        Ok(())
+    }
+
+    /// the game's decisions so far
+    pub fn transcript(&self) -> Transcript {
+        Transcript {
+            rules: self.rules,
+            players: self
+                .players
+                .iter()
+                .map(|player| player.name.clone())
+                .collect(),
+            moves: self.moves.clone(),
+        }
+    }
+
+    fn push_move(&mut self, decision: Decision) {
+        self.moves.push(Move {
+            player: self.current,
+            round: self.round,
+            dice: self.current_player().dice.clone(),
+            rolls_left: self.rolls_left(),
+            decision,
+        });
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            players: self.players.clone(),
+            moves: self.moves.clone(),
+            round: self.round,
+            current: self.current,
+            rolls: self.rolls,
+        }
+    }
+
+    fn restore(&mut self, snapshot: Snapshot) {
+        self.players = snapshot.players;
+        self.moves = snapshot.moves;
+        self.round = snapshot.round;
+        self.current = snapshot.current;
+        self.rolls = snapshot.rolls;
+    }
+
+    /// remembers the game as it is before a move that can be taken back
+    fn record(&mut self) {
+        self.undo.push(self.snapshot());
+        self.redo.clear();
+    }
+
+    /// a new turn starting ends the chance to take back the last one,
+    /// except in practice games
+    fn clear_history(&mut self) {
+        if !self.practice {
+            self.undo.clear();
+        }
+        self.redo.clear();
+    }
+
+    /// the dice for the roll about to be made, drawn the first time that
+    /// roll of the turn is made and the same every time after. Daily games
+    /// never draw from the generator.
+    fn outcome<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Vec<u32> {
+        let turn = (self.round - 1) * self.players.len() + self.current;
+        let roll = self.rolls as usize;
+        if self.outcomes.len() <= turn {
+            self.outcomes.resize(turn + 1, Vec::new());
+        }
+
+        let rolls = &mut self.outcomes[turn];
+        while rolls.len() <= roll {
+            let dice = match self.daily {
+                Some(date) => daily::dice(date.seed(), self.round, rolls.len() as u32, &self.rules),
+                None => {
+                    let die_range = Uniform::from(1..=self.rules.num_faces);
+                    (0..self.rules.num_dice)
+                        .map(|_| die_range.sample(rng))
+                        .collect()
+                }
+            };
+            rolls.push(dice);
+        }
+        rolls[roll].clone()
+    }
+
+    fn check_playing(&self) -> Result<(), GameError> {
+        if self.is_over() {
+            return Err(GameError::GameOver);
+        }
+        Ok(())
+    }
+
+    fn check_can_reroll(&self) -> Result<(), GameError> {
+        self.check_playing()?;
+        match self.rolls {
+            0 => Err(GameError::NotRolled),
+            rolls if rolls >= ROLLS_PER_TURN => Err(GameError::NoRollsLeft),
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,367 @@
+use crate::score::Category;
+use std::sync::OnceLock;
+
+/// number of distinct hands of five six-sided dice
+pub const NUM_HANDS: usize = 252;
+/// number of distinct sets of zero to five dice that can be held
+pub const NUM_KEEPS: usize = 462;
+
+const NUM_DICE: usize = 5;
+const NUM_FACES: usize = 6;
+const NO_RANK: u16 = u16::MAX;
+
+/// A roll of five dice with the order thrown away.
+///
+/// Every hand has an index between 0 and 252 which is used to look up its
+/// category scores and reroll outcomes from tables built on first use.
+///
+/// # Example
+/// ```rust
+/// use yahtzee::hand::DiceHand;
+/// use yahtzee::score::Category;
+///
+/// let hand = DiceHand::from_dice(&[5, 2, 5, 2, 5]).unwrap();
+/// assert_eq!(hand.dice(), [2, 2, 5, 5, 5]);
+/// assert_eq!(hand.score(Category::FullHouse), 25);
+/// assert_eq!(hand.score(Category::Fives), 15);
+/// assert_eq!(DiceHand::from_index(hand.index()), hand);
+/// ```
+#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Clone, Copy)]
+pub struct DiceHand(u8);
+
+/// The dice held back from a hand before the rest are rerolled.
+///
+/// # Example
+/// ```rust
+/// use yahtzee::hand::Keep;
+///
+/// let keep = Keep::from_dice(&[6, 6, 6, 6]).unwrap();
+/// let yahtzee_odds: f64 = keep
+///     .outcomes()
+///     .iter()
+///     .filter(|(hand, _)| hand.dice() == [6; 5])
+///     .map(|(_, probability)| probability)
+///     .sum();
+/// assert!((yahtzee_odds - 1.0 / 6.0).abs() < 1e-12);
+/// ```
+#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Clone, Copy)]
+pub struct Keep(u16);
+
+impl DiceHand {
+    /// the hand at an index between 0 and 252
+    pub fn from_index(index: usize) -> DiceHand {
+        assert!(index < NUM_HANDS, "hand index {} out of range", index);
+        DiceHand(index as u8)
+    }
+
+    /// every possible hand, in index order
+    pub fn all() -> impl Iterator<Item = DiceHand> {
+        (0..NUM_HANDS).map(DiceHand::from_index)
+    }
+
+    /// builds the hand for five dice, or `None` if the dice are not five
+    /// values between 1 and 6
+    pub fn from_dice(dice: &[u32]) -> Option<DiceHand> {
+        if dice.len() != NUM_DICE {
+            return None;
+        }
+        let counts = counts_of(dice)?;
+        match tables().hand_rank[key(&counts)] {
+            NO_RANK => None,
+            rank => Some(DiceHand(rank as u8)),
+        }
+    }
+
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+
+    /// how many of each face are in the hand, indexed by face - 1
+    pub fn counts(self) -> [u8; NUM_FACES] {
+        tables().hands[self.index()]
+    }
+
+    /// the dice in ascending order
+    pub fn dice(self) -> [u32; NUM_DICE] {
+        let mut dice = [0; NUM_DICE];
+        for (slot, face) in dice.iter_mut().zip(faces(&self.counts())) {
+            *slot = face;
+        }
+        dice
+    }
+
+    /// the points this hand is worth in a category on an empty scorecard
+    ///
+    /// Yahtzee bonuses depend on the scorecard and are not included.
+    ///
+    /// # Example
+    /// ```rust
+    /// use yahtzee::hand::DiceHand;
+    /// use yahtzee::player::Player;
+    ///
+    /// let mut player = Player::new("test".to_owned());
+    /// for hand in DiceHand::all() {
+    ///     player.dice = hand.dice().to_vec();
+    ///     for score in player.possible_scores() {
+    ///         assert_eq!(hand.score(score.category().unwrap()), score.value());
+    ///     }
+    /// }
+    /// ```
+    pub fn score(self, category: Category) -> u32 {
+        tables().scores[self.index()][category.index()]
+    }
+
+    /// the points this hand is worth in every category, indexed by
+    /// `Category::index`
+    pub fn scores(self) -> &'static [u32; Category::COUNT] {
+        &tables().scores[self.index()]
+    }
+
+    /// the dice kept when holding the positions set in `mask`, where bit `i`
+    /// refers to the `i`th die of `dice()`
+    pub fn keep(self, mask: u8) -> Keep {
+        tables().hand_keeps[self.index()][mask as usize & 0b11111]
+    }
+
+    /// every distinct set of dice that can be held from this hand
+    pub fn keeps(self) -> &'static [Keep] {
+        &tables().distinct_keeps[self.index()]
+    }
+
+    /// the hands that can follow holding `mask` and rerolling the rest
+    pub fn reroll(self, mask: u8) -> &'static [(DiceHand, f64)] {
+        self.keep(mask).outcomes()
+    }
+}
+
+impl Keep {
+    pub fn from_index(index: usize) -> Keep {
+        assert!(index < NUM_KEEPS, "keep index {} out of range", index);
+        Keep(index as u16)
+    }
+
+    /// the set of held dice for up to five values between 1 and 6
+    pub fn from_dice(dice: &[u32]) -> Option<Keep> {
+        if dice.len() > NUM_DICE {
+            return None;
+        }
+        let counts = counts_of(dice)?;
+        Some(Keep(tables().keep_rank[key(&counts)]))
+    }
+
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+
+    /// the held dice in ascending order
+    pub fn dice(self) -> Vec<u32> {
+        faces(&tables().keeps[self.index()]).collect()
+    }
+
+    /// the hands that can be rolled from this keep and their probabilities
+    pub fn outcomes(self) -> &'static [(DiceHand, f64)] {
+        &tables().outcomes[self.index()]
+    }
+}
+
+/// the distribution of hands from rolling all five dice
+///
+/// # Example
+/// ```rust
+/// use yahtzee::hand::{self, NUM_HANDS};
+///
+/// let total: f64 = hand::first_roll().iter().map(|(_, probability)| probability).sum();
+/// assert_eq!(hand::first_roll().len(), NUM_HANDS);
+/// assert!((total - 1.0).abs() < 1e-12);
+/// ```
+pub fn first_roll() -> &'static [(DiceHand, f64)] {
+    Keep::from_dice(&[]).unwrap().outcomes()
+}
+
+struct Tables {
+    hands: Vec<[u8; NUM_FACES]>,
+    hand_rank: Vec<u16>,
+    keeps: Vec<[u8; NUM_FACES]>,
+    keep_rank: Vec<u16>,
+    scores: Vec<[u32; Category::COUNT]>,
+    hand_keeps: Vec<[Keep; 32]>,
+    distinct_keeps: Vec<Vec<Keep>>,
+    outcomes: Vec<Vec<(DiceHand, f64)>>,
+}
+
+fn tables() -> &'static Tables {
+    static TABLES: OnceLock<Tables> = OnceLock::new();
+    TABLES.get_or_init(Tables::new)
+}
+
+impl Tables {
+    fn new() -> Tables {
+        let num_keys = (NUM_DICE + 1).pow(NUM_FACES as u32);
+
+        let hands = multisets(NUM_DICE);
+        let mut hand_rank = vec![NO_RANK; num_keys];
+        for (rank, counts) in hands.iter().enumerate() {
+            hand_rank[key(counts)] = rank as u16;
+        }
+
+        let keeps: Vec<[u8; NUM_FACES]> = (0..=NUM_DICE).flat_map(multisets).collect();
+        let mut keep_rank = vec![NO_RANK; num_keys];
+        for (rank, counts) in keeps.iter().enumerate() {
+            keep_rank[key(counts)] = rank as u16;
+        }
+
+        let scores = hands.iter().map(category_scores).collect();
+
+        let hand_keeps: Vec<[Keep; 32]> = hands
+            .iter()
+            .map(|counts| {
+                let dice: Vec<u32> = faces(counts).collect();
+                let mut keeps = [Keep(0); 32];
+                for (mask, keep) in keeps.iter_mut().enumerate() {
+                    let mut held = [0; NUM_FACES];
+                    for (i, die) in dice.iter().enumerate() {
+                        if mask & (1 << i) != 0 {
+                            held[*die as usize - 1] += 1;
+                        }
+                    }
+                    *keep = Keep(keep_rank[key(&held)]);
+                }
+                keeps
+            })
+            .collect();
+
+        let distinct_keeps = hand_keeps
+            .iter()
+            .map(|keeps| {
+                let mut keeps = keeps.to_vec();
+                keeps.sort_unstable();
+                keeps.dedup();
+                keeps
+            })
+            .collect();
+
+        let outcomes = keeps
+            .iter()
+            .map(|held| {
+                let rolled_dice =
+                    NUM_DICE - held.iter().map(|count| *count as usize).sum::<usize>();
+                let total = (NUM_FACES as f64).powi(rolled_dice as i32);
+                multisets(rolled_dice)
+                    .iter()
+                    .map(|rolled| {
+                        let mut counts = *held;
+                        for (count, extra) in counts.iter_mut().zip(rolled.iter()) {
+                            *count += extra;
+                        }
+                        let hand = DiceHand(hand_rank[key(&counts)] as u8);
+                        (hand, arrangements(rolled) / total)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Tables {
+            hands,
+            hand_rank,
+            keeps,
+            keep_rank,
+            scores,
+            hand_keeps,
+            distinct_keeps,
+            outcomes,
+        }
+    }
+}
+
+/// face counts for dice, or `None` if a die is not between 1 and 6
+fn counts_of(dice: &[u32]) -> Option<[u8; NUM_FACES]> {
+    let mut counts = [0; NUM_FACES];
+    for die in dice {
+        match *die as usize {
+            face @ 1..=NUM_FACES => counts[face - 1] += 1,
+            _ => return None,
+        }
+    }
+    Some(counts)
+}
+
+/// a unique number for face counts of at most five dice
+fn key(counts: &[u8; NUM_FACES]) -> usize {
+    counts
+        .iter()
+        .rev()
+        .fold(0, |key, count| key * (NUM_DICE + 1) + *count as usize)
+}
+
+/// the faces of the counted dice in ascending order
+fn faces(counts: &[u8; NUM_FACES]) -> impl Iterator<Item = u32> + '_ {
+    counts
+        .iter()
+        .enumerate()
+        .flat_map(|(face, count)| std::iter::repeat_n(face as u32 + 1, *count as usize))
+}
+
+/// every way of counting `num_dice` dice across the faces
+fn multisets(num_dice: usize) -> Vec<[u8; NUM_FACES]> {
+    fn fill(
+        face: usize,
+        remaining: u8,
+        counts: &mut [u8; NUM_FACES],
+        all: &mut Vec<[u8; NUM_FACES]>,
+    ) {
+        if face == NUM_FACES - 1 {
+            counts[face] = remaining;
+            all.push(*counts);
+            return;
+        }
+        for count in (0..=remaining).rev() {
+            counts[face] = count;
+            fill(face + 1, remaining - count, counts, all);
+        }
+    }
+
+    let mut all = vec![];
+    fill(0, num_dice as u8, &mut [0; NUM_FACES], &mut all);
+    all
+}
+
+/// number of orderings of the counted dice
+fn arrangements(counts: &[u8; NUM_FACES]) -> f64 {
+    let factorial = |n: u8| (1..=n as u32).map(f64::from).product::<f64>();
+    let total = counts.iter().copied().sum();
+    counts
+        .iter()
+        .fold(factorial(total), |ways, count| ways / factorial(*count))
+}
+
+fn category_scores(counts: &[u8; NUM_FACES]) -> [u32; Category::COUNT] {
+    let sum: u32 = faces(counts).sum();
+    let most_of_a_kind = *counts.iter().max().unwrap();
+    let mut longest_run = 0;
+    let mut run = 0;
+    for count in counts {
+        run = if *count > 0 { run + 1 } else { 0 };
+        longest_run = longest_run.max(run);
+    }
+
+    let mut scores = [0; Category::COUNT];
+    for category in Category::STANDARD.iter() {
+        scores[category.index()] = match category {
+            Category::Aces => counts[0] as u32,
+            Category::Twos => counts[1] as u32 * 2,
+            Category::Threes => counts[2] as u32 * 3,
+            Category::Fours => counts[3] as u32 * 4,
+            Category::Fives => counts[4] as u32 * 5,
+            Category::Sixes => counts[5] as u32 * 6,
+            Category::ThreeOfAKind if most_of_a_kind >= 3 => sum,
+            Category::FourOfAKind if most_of_a_kind >= 4 => sum,
+            Category::FullHouse if counts.contains(&3) && counts.contains(&2) => 25,
+            Category::SmallStraight if longest_run >= 4 => 30,
+            Category::LargeStraight if longest_run >= 5 => 40,
+            Category::Chance => sum,
+            Category::Yahtzee if most_of_a_kind == 5 => 50,
+            _ => 0,
+        };
+    }
+    scores
+}
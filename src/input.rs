@@ -1,12 +1,59 @@
-use std::{io, str};
+use crate::score::{Category, ParseCategoryError};
+use rustyline::completion::Completer;
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::{Context, Editor, Helper};
+use std::io::Write;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use std::{fmt, io, str, thread};
+
+/// lines of standard input read by a thread of their own, started the first
+/// time input has to be waited for with a deadline
+static LINES: OnceLock<Mutex<Receiver<io::Result<String>>>> = OnceLock::new();
+
+/// the lines read by the input thread, starting it if it hasn't been
+fn lines() -> &'static Mutex<Receiver<io::Result<String>>> {
+    LINES.get_or_init(|| {
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || loop {
+            let mut line = String::new();
+            let read = io::stdin().read_line(&mut line);
+            // an empty line is the end of the input
+            let done = !matches!(read, Ok(length) if length > 0);
+            if sender.send(read.map(|_| line)).is_err() || done {
+                break;
+            }
+        });
+        Mutex::new(receiver)
+    })
+}
+
+/// reads a line like `Stdin::read_line`, but from the input thread once
+/// there is one, so it can't take a line another read is waiting for
+fn read_stdin(buf: &mut String) -> io::Result<usize> {
+    match LINES.get() {
+        Some(lines) => match lines.lock().expect("input thread panicked").recv() {
+            Ok(Ok(line)) => {
+                buf.push_str(&line);
+                Ok(line.len())
+            }
+            Ok(Err(error)) => Err(error),
+            Err(_) => Ok(0),
+        },
+        None => io::stdin().read_line(buf),
+    }
+}
 
 /// read value from and parse into type of passed in argument T
 pub fn read_value<T: str::FromStr>() -> Result<T, T::Err> {
     let mut input = String::new();
 
-    io::stdin()
-        .read_line(&mut input)
-        .expect("Failed to read line");
+    read_stdin(&mut input).expect("Failed to read line");
 
     input.trim().parse()
 }
@@ -16,50 +63,381 @@ pub fn read_values<T: str::FromStr>() -> Option<Result<Vec<T>, T::Err>> {
     let mut input = String::new();
 
     loop {
-        match io::stdin().read_line(&mut input) {
+        match read_stdin(&mut input) {
             Ok(_) if input.trim() == "" => return None,
-            Ok(_) => {
-                return Some(
-                    input
-                        .trim()
-                        .split_whitespace()
-                        .map(|word| word.parse())
-                        .collect(),
+            Ok(_) => return Some(input.split_whitespace().map(|word| word.parse()).collect()),
+            Err(error) => println!("Error: {}", error),
+        };
+    }
+}
+
+/// A command entered during a turn
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Command {
+    /// list the commands
+    Help,
+    /// show the scorecard
+    Card,
+    /// reroll the dice at these positions
+    Reroll(Vec<u8>),
+    /// keep dice showing these values and reroll the rest
+    Keep(Vec<u32>),
+    /// reroll every die
+    Roll,
+    /// show the odds of reaching each target when rerolling these positions
+    Probability(Vec<u8>),
+    /// stop rolling and pick a score
+    Stop,
+    /// score the dice in a category
+    Score(Category),
+    /// take back the last move
+    Undo,
+    /// put back the last move taken back
+    Redo,
+    /// show the best move
+    Hint,
+    /// save the game, to a file if one is given
+    Save(Option<String>),
+    Quit,
+}
+
+/// Reasons a line couldn't be read as a command
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum CommandError {
+    UnknownCommand(String),
+    MissingArgument(&'static str),
+    UnexpectedArgument(String),
+    InvalidNumber(String),
+    InvalidCategory(ParseCategoryError),
+    Io(io::ErrorKind),
+    EndOfInput,
+    /// nothing was entered before the time ran out
+    OutOfTime,
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommandError::UnknownCommand(command) => {
+                write!(f, "unknown command \"{}\", type help for a list", command)
+            }
+            CommandError::MissingArgument(command) => {
+                write!(
+                    f,
+                    "{} needs something to act on, type help for details",
+                    command
                 )
             }
-            Err(error) => println!("Error: {}", error),
+            CommandError::UnexpectedArgument(argument) => {
+                write!(f, "unexpected \"{}\"", argument)
+            }
+            CommandError::InvalidNumber(number) => write!(f, "\"{}\" is not a number", number),
+            CommandError::InvalidCategory(error) => write!(f, "{}", error),
+            CommandError::Io(kind) => write!(f, "could not read input: {:?}", kind),
+            CommandError::EndOfInput => write!(f, "no more input"),
+            CommandError::OutOfTime => write!(f, "out of time"),
+        }
+    }
+}
+
+impl From<ParseCategoryError> for CommandError {
+    fn from(error: ParseCategoryError) -> CommandError {
+        CommandError::InvalidCategory(error)
+    }
+}
+
+/// descriptions of the commands for the help command
+pub const COMMAND_HELP: &[(&str, &str)] = &[
+    ("<dice>", "reroll the dice at these positions, e.g. 1 4 5"),
+    (
+        "keep <values>",
+        "keep dice showing these values and reroll the rest, e.g. keep 5 5",
+    ),
+    ("roll", "reroll every die"),
+    (
+        "prob [dice]",
+        "show the odds of rerolling the dice at these positions",
+    ),
+    (
+        "score <category>",
+        "score a category by name or code, e.g. score fh",
+    ),
+    ("<category>", "the same as score, e.g. 3k"),
+    ("(nothing)", "stop rolling and pick a score"),
+    ("card", "show the scorecard"),
+    (
+        "undo",
+        "take back the last score, or any move when practicing",
+    ),
+    ("redo", "put back the last move taken back"),
+    (
+        "hint",
+        "show the best move, and the chance of reaching the target if there is one",
+    ),
+    ("save [file]", "save the game to continue later"),
+    ("quit", "leave the game"),
+    ("help", "show this list"),
+];
+
+fn parse_numbers<T: str::FromStr>(words: &[&str]) -> Result<Vec<T>, CommandError> {
+    words
+        .iter()
+        .map(|word| {
+            word.parse()
+                .map_err(|_| CommandError::InvalidNumber((*word).to_owned()))
+        })
+        .collect()
+}
+
+impl str::FromStr for Command {
+    type Err = CommandError;
+
+    /// Parses a command, where a line of numbers rerolls those dice, a
+    /// category on its own scores it and an empty line stops rolling.
+    ///
+    /// # Example
+    /// ```rust
+    /// use yahtzee::input::{Command, CommandError};
+    /// use yahtzee::score::Category;
+    ///
+    /// assert_eq!("1 4".parse(), Ok(Command::Reroll(vec![1, 4])));
+    /// assert_eq!("keep 5 5".parse(), Ok(Command::Keep(vec![5, 5])));
+    /// assert_eq!("score fh".parse(), Ok(Command::Score(Category::FullHouse)));
+    /// assert_eq!("3k".parse(), Ok(Command::Score(Category::ThreeOfAKind)));
+    /// assert_eq!("".parse(), Ok(Command::Stop));
+    /// assert_eq!("keep".parse::<Command>(), Err(CommandError::MissingArgument("keep")));
+    /// assert_eq!("roll 2".parse::<Command>(), Err(CommandError::UnexpectedArgument("2".to_owned())));
+    /// ```
+    fn from_str(input: &str) -> Result<Command, CommandError> {
+        let words: Vec<&str> = input.split_whitespace().collect();
+        let (first, rest) = match words.split_first() {
+            Some((first, rest)) => (first.to_lowercase(), rest),
+            None => return Ok(Command::Stop),
         };
+
+        let no_arguments = |command: Command| match rest.first() {
+            Some(argument) => Err(CommandError::UnexpectedArgument((*argument).to_owned())),
+            None => Ok(command),
+        };
+
+        match first.as_str() {
+            "help" | "?" => no_arguments(Command::Help),
+            "card" => no_arguments(Command::Card),
+            "roll" => no_arguments(Command::Roll),
+            "undo" => no_arguments(Command::Undo),
+            "redo" => no_arguments(Command::Redo),
+            "hint" => no_arguments(Command::Hint),
+            "quit" | "exit" => no_arguments(Command::Quit),
+            "keep" if rest.is_empty() => Err(CommandError::MissingArgument("keep")),
+            "keep" => parse_numbers(rest).map(Command::Keep),
+            "prob" => parse_numbers(rest).map(Command::Probability),
+            "score" if rest.is_empty() => Err(CommandError::MissingArgument("score")),
+            "score" => Ok(Command::Score(rest.join(" ").parse()?)),
+            "save" if rest.len() > 1 => Err(CommandError::UnexpectedArgument(rest[1].to_owned())),
+            "save" => Ok(Command::Save(rest.first().map(|path| (*path).to_owned()))),
+            _ if first.chars().all(|c| c.is_ascii_digit()) => {
+                parse_numbers(&words).map(Command::Reroll)
+            }
+            _ => match input.parse::<Category>() {
+                Ok(category) => Ok(Command::Score(category)),
+                Err(_) => Err(CommandError::UnknownCommand(first)),
+            },
+        }
+    }
+}
+
+/// read a command, with tab completion of the passed in categories when
+/// reading from a terminal
+pub fn read_command(categories: &[Category]) -> Result<Command, CommandError> {
+    match read_line(categories) {
+        Ok(Some(input)) => input.parse(),
+        Ok(None) => Err(CommandError::EndOfInput),
+        Err(error) => Err(CommandError::Io(error.kind())),
+    }
+}
+
+/// Reads a command like `read_command`, but gives up with
+/// `CommandError::OutOfTime` if nothing is entered within the time given.
+/// Waiting on a deadline needs the input thread, so categories aren't
+/// completed.
+pub fn read_command_within(
+    categories: &[Category],
+    time: Option<Duration>,
+) -> Result<Command, CommandError> {
+    let time = match time {
+        Some(time) => time,
+        None => return read_command(categories),
+    };
+    print!("> ");
+    io::stdout()
+        .flush()
+        .map_err(|error| CommandError::Io(error.kind()))?;
+    match lines()
+        .lock()
+        .expect("input thread panicked")
+        .recv_timeout(time)
+    {
+        Ok(Ok(input)) if input.is_empty() => Err(CommandError::EndOfInput),
+        Ok(Ok(input)) => input.parse(),
+        Ok(Err(error)) => Err(CommandError::Io(error.kind())),
+        Err(RecvTimeoutError::Timeout) => Err(CommandError::OutOfTime),
+        Err(RecvTimeoutError::Disconnected) => Err(CommandError::EndOfInput),
     }
 }
 
 /// Gets the players name from standard input
 pub fn get_player_name() -> Option<String> {
-    let mut player_name = String::new();
+    get_name("What is your name?")
+}
+
+/// asks for a name on standard input, returning None if nothing is entered
+pub fn get_name(prompt: &str) -> Option<String> {
+    let mut name = String::new();
     loop {
-        println!("What is your name?");
+        println!("{}", prompt);
 
-        match io::stdin().read_line(&mut player_name) {
-            Ok(_) if player_name.trim() == "" => return None,
-            Ok(_) => return Some(player_name.trim().to_owned()),
+        match read_stdin(&mut name) {
+            Ok(_) if name.trim() == "" => return None,
+            Ok(_) => return Some(name.trim().to_owned()),
             Err(error) => println!("Error: {}", error),
         }
     }
 }
 
-pub fn get_dice<T: str::FromStr>() -> Option<Result<Vec<T>, T::Err>> {
+/// Reads the values of dice rolled outside of the game, split by whitespace.
+/// A blank line gives no values, and nothing left to read gives
+/// `CommandError::EndOfInput`.
+pub fn get_dice<T: str::FromStr>() -> Result<Vec<T>, CommandError> {
     let mut dice = String::new();
-    loop {
-        match io::stdin().read_line(&mut dice) {
-            Ok(_) if dice.trim() == "" => return None,
-            Ok(_) => {
-                return Some(
-                    dice.trim()
-                        .split_whitespace()
-                        .map(|word| word.parse())
-                        .collect(),
-                )
+    match read_stdin(&mut dice) {
+        Ok(0) => Err(CommandError::EndOfInput),
+        Ok(_) => parse_numbers(&dice.split_whitespace().collect::<Vec<&str>>()),
+        Err(error) => Err(CommandError::Io(error.kind())),
+    }
+}
+
+/// Tab completion for the names and codes of categories that can be scored
+pub struct CategoryCompleter {
+    categories: Vec<Category>,
+}
+
+impl CategoryCompleter {
+    pub fn new(categories: Vec<Category>) -> CategoryCompleter {
+        CategoryCompleter { categories }
+    }
+
+    /// the category names and codes that start with what's been typed
+    ///
+    /// # Example
+    /// ```rust
+    /// use yahtzee::input::CategoryCompleter;
+    /// use yahtzee::score::Category;
+    ///
+    /// let completer = CategoryCompleter::new(vec![Category::Fours, Category::FourOfAKind, Category::FullHouse]);
+    /// assert_eq!(completer.candidates("fou"), vec!["fours", "four of a kind"]);
+    /// assert_eq!(completer.candidates("F"), vec!["fours", "four of a kind", "full house", "fh"]);
+    /// ```
+    pub fn candidates(&self, typed: &str) -> Vec<String> {
+        let typed = typed.trim_start().to_lowercase();
+        let names = self
+            .categories
+            .iter()
+            .map(|category| category.to_string().to_lowercase());
+        let codes = self
+            .categories
+            .iter()
+            .map(|category| category.code().to_owned());
+
+        names
+            .chain(codes)
+            .filter(|candidate| candidate.starts_with(&typed))
+            .collect()
+    }
+}
+
+impl Completer for CategoryCompleter {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        let typed = &line[..pos];
+        match typed.find(char::is_whitespace) {
+            Some(end) if typed[..end].eq_ignore_ascii_case("score") => {
+                let start = end + typed[end..].len() - typed[end..].trim_start().len();
+                Ok((start, self.candidates(&typed[start..])))
+            }
+            _ => Ok((0, self.candidates(typed))),
+        }
+    }
+}
+
+impl Hinter for CategoryCompleter {
+    type Hint = String;
+}
+
+impl Highlighter for CategoryCompleter {}
+
+impl Validator for CategoryCompleter {}
+
+impl Helper for CategoryCompleter {}
+
+/// whether categories can be tab completed, which they can't once a timed
+/// game has started the input thread, for the rest of the run
+pub fn completes() -> bool {
+    LINES.get().is_none()
+}
+
+/// read a line, completing the passed in categories when reading from a
+/// terminal, with nothing left to read giving None
+fn read_line(categories: &[Category]) -> io::Result<Option<String>> {
+    // the terminal can't be shared with the input thread
+    let editor = match completes() {
+        true => Editor::<CategoryCompleter, DefaultHistory>::new().ok(),
+        false => None,
+    };
+    match editor {
+        Some(mut editor) => {
+            editor.set_helper(Some(CategoryCompleter::new(categories.to_vec())));
+            match editor.readline("> ") {
+                Ok(input) => Ok(Some(input)),
+                Err(ReadlineError::Eof) | Err(ReadlineError::Interrupted) => Ok(None),
+                Err(ReadlineError::Io(error)) => Err(error),
+                Err(error) => Err(io::Error::other(error)),
             }
-            Err(error) => println!("Error: {}", error),
         }
+        None => {
+            let mut input = String::new();
+            match read_stdin(&mut input)? {
+                0 => Ok(None),
+                _ => Ok(Some(input)),
+            }
+        }
+    }
+}
+
+/// Reads a category name or code, with tab completion of the passed in
+/// categories when reading from a terminal. A blank line gives None, and
+/// nothing left to read gives `CommandError::EndOfInput`.
+pub fn read_category(categories: &[Category]) -> Result<Option<Category>, CommandError> {
+    match read_line(categories) {
+        Ok(Some(input)) if input.trim().is_empty() => Ok(None),
+        Ok(Some(input)) => Ok(Some(input.parse()?)),
+        Ok(None) => Err(CommandError::EndOfInput),
+        Err(error) => Err(CommandError::Io(error.kind())),
+    }
+}
+
+/// ask a yes or no question, where anything but yes counts as no
+pub fn confirm(question: &str) -> bool {
+    println!("{} (y/n)", question);
+
+    let mut input = String::new();
+    match read_stdin(&mut input) {
+        Ok(_) => matches!(input.trim().to_lowercase().as_str(), "y" | "yes"),
+        Err(_) => false,
     }
 }
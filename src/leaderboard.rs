@@ -0,0 +1,209 @@
+use crate::daily::Date;
+use crate::game::Game;
+use crate::player::Player;
+use crate::rules::Rules;
+use crate::storage;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+/// file in the data directory that holds the high scores
+pub const LEADERBOARD_FILE: &str = "leaderboard.json";
+
+/// how many scores each board keeps
+pub const LEADERBOARD_SIZE: usize = 10;
+
+/// Which scores are compared with each other. Games with other rules or
+/// against other players aren't comparable, so each gets its own board.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct Board {
+    pub rules: Rules,
+    pub multiplayer: bool,
+}
+
+impl fmt::Display for Board {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.multiplayer {
+            true => write!(f, "{} multiplayer", self.rules),
+            false => write!(f, "{} solo", self.rules),
+        }
+    }
+}
+
+/// One finished game's score
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct HighScore {
+    pub name: String,
+    pub score: u32,
+    pub date: Date,
+    pub rules: Rules,
+    /// the seed the dice came from, for games that can be replayed
+    pub seed: Option<u64>,
+    /// whether anyone else played
+    pub multiplayer: bool,
+    /// whether any bots played
+    pub bots: bool,
+}
+
+impl HighScore {
+    pub fn board(&self) -> Board {
+        Board {
+            rules: self.rules,
+            multiplayer: self.multiplayer,
+        }
+    }
+}
+
+/// The best scores on every board, stored as JSON.
+///
+/// # Example
+/// ```rust
+/// use yahtzee::daily::Date;
+/// use yahtzee::leaderboard::{HighScore, Leaderboard, LEADERBOARD_SIZE};
+/// use yahtzee::rules::Rules;
+///
+/// let high_score = |score, multiplayer| HighScore {
+///     name: "Ann".to_owned(),
+///     score,
+///     date: Date { year: 2024, month: 5, day: 1 },
+///     rules: Rules::default(),
+///     seed: None,
+///     multiplayer,
+///     bots: false,
+/// };
+/// let mut leaderboard = Leaderboard::default();
+/// for score in 0..LEADERBOARD_SIZE as u32 {
+///     assert_eq!(leaderboard.record(high_score(200 + score, false)), Some(1));
+/// }
+/// assert_eq!(leaderboard.record(high_score(205, false)), Some(6));
+/// // places are counted once everyone in a game is on the board
+/// let game = vec![high_score(203, false), high_score(207, false)];
+/// assert_eq!(leaderboard.record_all(game), vec![Some(10), Some(4)]);
+/// assert_eq!(leaderboard.record(high_score(100, false)), None);
+/// // multiplayer games have their own board
+/// assert_eq!(leaderboard.record(high_score(100, true)), Some(1));
+///
+/// let solo = leaderboard.board(high_score(0, false).board());
+/// assert_eq!(solo.len(), LEADERBOARD_SIZE);
+/// assert_eq!(solo[0].score, 209);
+/// assert_eq!(solo[LEADERBOARD_SIZE - 1].score, 203);
+/// assert_eq!(leaderboard.boards().len(), 2);
+/// ```
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Leaderboard {
+    /// every board's scores, best first
+    pub scores: Vec<HighScore>,
+}
+
+impl Leaderboard {
+    /// reads the high scores saved in a file, with every board empty if
+    /// nothing has been saved yet
+    pub fn load(path: &Path) -> io::Result<Leaderboard> {
+        storage::load_json(path)
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        storage::save_json(path, self)
+    }
+
+    /// the scores on one board, best first
+    pub fn board(&self, board: Board) -> Vec<&HighScore> {
+        self.scores
+            .iter()
+            .filter(|score| score.board() == board)
+            .collect()
+    }
+
+    /// every board with a score on it, in the order of their best scores
+    pub fn boards(&self) -> Vec<Board> {
+        let mut boards: Vec<Board> = Vec::new();
+        for score in self.scores.iter() {
+            if !boards.contains(&score.board()) {
+                boards.push(score.board());
+            }
+        }
+        boards
+    }
+
+    /// Adds a score to its board, returning its place if it made the board.
+    /// A score level with one already there goes below it.
+    pub fn record(&mut self, high_score: HighScore) -> Option<usize> {
+        self.record_all(vec![high_score])[0]
+    }
+
+    /// Adds the scores from one game to their boards, returning the place of
+    /// each once they're all on, or None for those that didn't make it.
+    pub fn record_all(&mut self, high_scores: Vec<HighScore>) -> Vec<Option<usize>> {
+        let mut indices: Vec<usize> = Vec::with_capacity(high_scores.len());
+        for high_score in high_scores {
+            let index = self
+                .scores
+                .iter()
+                .position(|score| score.score < high_score.score)
+                .unwrap_or(self.scores.len());
+            for earlier in indices.iter_mut().filter(|earlier| **earlier >= index) {
+                *earlier += 1;
+            }
+            indices.push(index);
+            self.scores.insert(index, high_score);
+        }
+
+        let places = indices
+            .iter()
+            .map(|index| {
+                let board = self.scores[*index].board();
+                let place = self.scores[..=*index]
+                    .iter()
+                    .filter(|score| score.board() == board)
+                    .count();
+                Some(place).filter(|place| *place <= LEADERBOARD_SIZE)
+            })
+            .collect();
+        let mut kept: HashMap<Board, usize> = HashMap::new();
+        self.scores.retain(|score| {
+            let count = kept.entry(score.board()).or_insert(0);
+            *count += 1;
+            *count <= LEADERBOARD_SIZE
+        });
+        places
+    }
+}
+
+/// Adds the scores of people who played a finished game to the leaderboard
+/// in the data directory, returning the name and place of everyone who made
+/// it.
+pub fn record_game(game: &Game, people: &[Player], date: Date) -> io::Result<Vec<(String, usize)>> {
+    let path = storage::data_file(LEADERBOARD_FILE)?;
+    let mut leaderboard = Leaderboard::load(&path)?;
+    let bots = game.bots.iter().any(Option::is_some);
+
+    let high_scores = people
+        .iter()
+        .map(|player| HighScore {
+            name: player.name.clone(),
+            score: player.score,
+            date,
+            rules: game.rules,
+            seed: game.daily.map(|date| date.seed()),
+            multiplayer: game.players.len() > 1,
+            bots,
+        })
+        .collect();
+    let places: Vec<(String, usize)> = people
+        .iter()
+        .zip(leaderboard.record_all(high_scores))
+        .filter_map(|(player, place)| Some((player.name.clone(), place?)))
+        .collect();
+
+    if !places.is_empty() {
+        leaderboard.save(&path)?;
+    }
+    Ok(places)
+}
+
+/// Reads the leaderboard in the data directory.
+pub fn load() -> io::Result<Leaderboard> {
+    Leaderboard::load(&storage::data_file(LEADERBOARD_FILE)?)
+}
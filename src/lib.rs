@@ -1,3 +1,27 @@
+pub mod analysis;
+pub mod clock;
+pub mod daily;
+pub mod endgame;
+pub mod engine;
+pub mod env;
+pub mod game;
+pub mod hand;
 pub mod input;
+pub mod leaderboard;
+pub mod match_play;
 pub mod player;
+pub mod probability;
+pub mod ratings;
+pub mod rules;
 pub mod score;
+pub mod solver;
+pub mod standings;
+pub mod stats;
+pub mod storage;
+pub mod strategy;
+pub mod team;
+pub mod threshold;
+pub mod tournament;
+pub mod trainer;
+pub mod transcript;
+pub mod turn_order;
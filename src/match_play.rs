@@ -0,0 +1,186 @@
+use crate::player::Player;
+use crate::standings::{self, Standing, TieBreak};
+use serde::{Deserialize, Serialize};
+use std::{fmt, str};
+
+/// How the games of a match add up to its result
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub enum MatchScoring {
+    /// the most points across every game wins
+    #[default]
+    Total,
+    /// the most games won wins, with points separating players level on wins
+    GamesWon,
+}
+
+impl fmt::Display for MatchScoring {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MatchScoring::Total => write!(f, "total points"),
+            MatchScoring::GamesWon => write!(f, "games won"),
+        }
+    }
+}
+
+/// The text given wasn't the name of a way to score a match
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ParseMatchScoringError(pub String);
+
+impl fmt::Display for ParseMatchScoringError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown match scoring \"{}\", use total or wins", self.0)
+    }
+}
+
+impl str::FromStr for MatchScoring {
+    type Err = ParseMatchScoringError;
+
+    fn from_str(input: &str) -> Result<MatchScoring, ParseMatchScoringError> {
+        match input.trim().to_lowercase().as_str() {
+            "total" => Ok(MatchScoring::Total),
+            "wins" => Ok(MatchScoring::GamesWon),
+            _ => Err(ParseMatchScoringError(input.to_owned())),
+        }
+    }
+}
+
+/// How one game of a match finished
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct MatchGame {
+    /// every player's score, in the match's order of players
+    pub scores: Vec<u32>,
+    /// every player's place, in the match's order of players
+    pub places: Vec<usize>,
+}
+
+/// A series of games between the same players, with who goes first moving
+/// one seat along each game.
+///
+/// # Example
+/// ```rust
+/// use yahtzee::match_play::{Match, MatchScoring};
+/// use yahtzee::player::Player;
+/// use yahtzee::score::Score;
+/// use yahtzee::standings::TieBreak;
+///
+/// let names = vec!["Ann".to_owned(), "Bob".to_owned(), "Cat".to_owned()];
+/// let mut series = Match::new(names, Some(3), MatchScoring::GamesWon);
+/// let finish = |series: &Match, scores: [u32; 3]| -> Vec<Player> {
+///     series
+///         .seating()
+///         .iter()
+///         .map(|player| {
+///             let mut card = Player::new(series.players[*player].clone());
+///             card.update_score(Score::Chance(scores[*player]));
+///             card
+///         })
+///         .collect()
+/// };
+///
+/// assert_eq!(series.seating(), vec![0, 1, 2]);
+/// series.record(&finish(&series, [30, 20, 10]), TieBreak::Shared);
+/// assert_eq!(series.seating(), vec![1, 2, 0]);
+/// series.record(&finish(&series, [30, 25, 28]), TieBreak::Shared);
+/// // Ann has won two of the three games
+/// assert!(series.is_over());
+///
+/// let standings = series.standings();
+/// assert_eq!((standings[0].name.as_str(), standings[0].score), ("Ann", 2));
+/// assert_eq!(series.totals(), vec![60, 45, 38]);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct Match {
+    /// the players in the order they sat for the first game
+    pub players: Vec<String>,
+    /// how many games the match is, or None to play until the players stop
+    pub length: Option<usize>,
+    pub scoring: MatchScoring,
+    pub games: Vec<MatchGame>,
+}
+
+impl Match {
+    pub fn new(players: Vec<String>, length: Option<usize>, scoring: MatchScoring) -> Match {
+        Match {
+            players,
+            length,
+            scoring,
+            games: Vec::new(),
+        }
+    }
+
+    /// the players in seat order for the next game, by their index in the match
+    pub fn seating(&self) -> Vec<usize> {
+        let count = self.players.len();
+        let first = self.games.len() % count.max(1);
+        (first..count).chain(0..first).collect()
+    }
+
+    /// adds a game, with the players seated as `seating` had them
+    pub fn record(&mut self, players: &[Player], tie_break: TieBreak) {
+        let seating = self.seating();
+        let places = standings::places(players, tie_break);
+        let mut game = MatchGame {
+            scores: vec![0; self.players.len()],
+            places: vec![0; self.players.len()],
+        };
+        for (seat, player) in seating.into_iter().enumerate() {
+            game.scores[player] = players[seat].score;
+            game.places[player] = places[seat];
+        }
+        self.games.push(game);
+    }
+
+    /// every player's points across the games so far
+    pub fn totals(&self) -> Vec<u32> {
+        (0..self.players.len())
+            .map(|player| self.games.iter().map(|game| game.scores[player]).sum())
+            .collect()
+    }
+
+    /// how many games every player has won, counting shared wins
+    pub fn wins(&self) -> Vec<u32> {
+        (0..self.players.len())
+            .map(|player| {
+                self.games
+                    .iter()
+                    .filter(|game| game.places[player] == 1)
+                    .count() as u32
+            })
+            .collect()
+    }
+
+    /// Whether every game has been played, or when counting games won, the
+    /// leader can't be caught in the games left.
+    pub fn is_over(&self) -> bool {
+        let length = match self.length {
+            Some(length) => length,
+            None => return false,
+        };
+        if self.games.len() >= length {
+            return true;
+        }
+        if self.scoring == MatchScoring::Total {
+            return false;
+        }
+
+        let left = (length - self.games.len()) as u32;
+        let mut wins = self.wins();
+        wins.sort_unstable_by(|a, b| b.cmp(a));
+        match wins.as_slice() {
+            [first, second, ..] => *first > second + left,
+            _ => false,
+        }
+    }
+
+    /// the players ranked by the match's scoring, each standing's score
+    /// being their total points or games won
+    pub fn standings(&self) -> Vec<Standing> {
+        let totals = self.totals();
+        let wins = self.wins();
+        let entries: Vec<(u32, u32)> = match self.scoring {
+            MatchScoring::Total => totals.into_iter().zip(wins).collect(),
+            MatchScoring::GamesWon => wins.into_iter().zip(totals).collect(),
+        };
+        standings::to_standings(&entries, |player| self.players[player].clone())
+    }
+}
@@ -1,49 +1,73 @@
-use crate::score::Score;
+use crate::rules::Rules;
+use crate::score::{Category, Score};
 use rand::distributions::{Distribution, Uniform};
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 use std::fmt;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct Player {
     pub name: String,
     pub score: u32,
-    pub dice: [u32; 5],
+    pub dice: Vec<u32>,
     pub scores: Vec<Score>,
+    pub rules: Rules,
 }
 
 impl Player {
     pub fn roll_dice(&mut self) {
-        let mut rng = rand::thread_rng();
-        let die_range = Uniform::from(1..7);
+        self.roll_dice_with(&mut rand::thread_rng());
+    }
+
+    /// Randomizes all the dice using the passed in random number generator
+    ///
+    /// # Example
+    /// ```rust
+    /// use rand::rngs::StdRng;
+    /// use rand::SeedableRng;
+    /// use yahtzee::player::Player;
+    ///
+    /// let mut first = Player::new("first".to_owned());
+    /// let mut second = Player::new("second".to_owned());
+    /// first.roll_dice_with(&mut StdRng::seed_from_u64(7));
+    /// second.roll_dice_with(&mut StdRng::seed_from_u64(7));
+    /// assert_eq!(first.dice, second.dice);
+    /// ```
+    pub fn roll_dice_with<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        let die_range = Uniform::from(1..=self.rules.num_faces);
 
         for die in self.dice.iter_mut() {
-            *die = die_range.sample(&mut rng);
+            *die = die_range.sample(rng);
         }
     }
 
-    /// randomizes a single die for a player between 1 and 6 inclusive
+    /// randomizes a single die for a player between 1 and the number of faces inclusive
     ///
     /// ```rust
     /// use yahtzee::player::Player;
     ///
     /// let mut player = Player::new("test".to_owned());
-    /// let old_die = player.dice[0];
-    /// player.roll_die(0);
+    /// player.reroll(vec![1]);
+    /// assert!((1..=6).contains(&player.dice[0]));
     /// ```
-    fn roll_die(&mut self, die: usize) {
+    fn roll_die<R: Rng + ?Sized>(&mut self, die: usize, rng: &mut R) {
         if die > self.dice.len() - 1 {
             println!("out of bounds");
             return;
         }
 
-        let mut rng = rand::thread_rng();
-        self.dice[die] = rng.gen_range(1, 7);
+        self.dice[die] = rng.gen_range(1, self.rules.num_faces + 1);
     }
 
     /// rerolls dice the user chooses to reroll
     pub fn reroll(&mut self, dice: Vec<u8>) {
+        self.reroll_with(dice, &mut rand::thread_rng());
+    }
+
+    /// rerolls the chosen dice using the passed in random number generator
+    pub fn reroll_with<R: Rng + ?Sized>(&mut self, dice: Vec<u8>, rng: &mut R) {
         for die in dice {
-            self.roll_die(die as usize - 1);
+            self.roll_die(die as usize - 1, rng);
         }
     }
 }
@@ -56,14 +80,31 @@ impl Player {
     /// use yahtzee::player::Player;
     ///
     /// let player = Player::new("test".to_owned());
-    /// assert_eq!(player, Player{name: "test".to_owned(), score: 0, dice: [0; 5], scores: vec![]});
+    /// assert_eq!(player, Player{name: "test".to_owned(), score: 0, dice: vec![0; 5], scores: vec![], rules: Default::default()});
     /// ```
     pub fn new(name: String) -> Player {
+        Player::with_rules(name, Rules::default())
+    }
+
+    /// Constructer for a Player using dice other than five six-sided ones
+    ///
+    /// # Example
+    /// ```rust
+    /// use yahtzee::player::Player;
+    /// use yahtzee::rules::Rules;
+    ///
+    /// let mut player = Player::with_rules("test".to_owned(), Rules::new(6, 8).unwrap());
+    /// player.roll_dice();
+    /// assert_eq!(player.dice.len(), 6);
+    /// assert!(player.dice.iter().all(|die| (1..=8).contains(die)));
+    /// ```
+    pub fn with_rules(name: String, rules: Rules) -> Player {
         Player {
-            name: name,
+            name,
             score: 0,
-            dice: [0; 5],
+            dice: vec![0; rules.num_dice],
             scores: vec![],
+            rules,
         }
     }
 
@@ -74,7 +115,7 @@ impl Player {
     /// use yahtzee::player::Player;
     ///
     /// let mut player = Player::new("test".to_owned());
-    /// let old_dice = player.dice;
+    /// let old_dice = player.dice.clone();
     /// player.roll_dice();
     /// assert_ne!(player.dice, old_dice);
     /// ```
@@ -86,7 +127,7 @@ impl Player {
     /// use yahtzee::score::Score;
     ///
     /// let mut player = Player::new("test".to_owned());
-    /// player.dice = [1,2,4,2,3];
+    /// player.dice = vec![1,2,4,2,3];
     /// let scores = player.possible_scores();
     /// assert_eq!(scores,
     ///     vec![Score::Aces(1),
@@ -103,10 +144,10 @@ impl Player {
     ///         Score::FullHouse(0),
     ///         Score::Chance(player.dice.iter().sum())]);
     /// ```
-    pub fn possible_scores(&mut self) -> Vec<Score> {
+    pub fn possible_scores(&self) -> Vec<Score> {
         let mut scores: Vec<Score> = vec![];
 
-        for die_face in 1..=6 {
+        for die_face in 1..=self.rules.num_faces {
             if let Some(upper_score) = Score::upper_score(self, die_face) {
                 scores.push(upper_score);
             };
@@ -143,7 +184,35 @@ impl Player {
         scores
     }
 
-    /// update player score and scores
+    /// the score the dice are worth in a category, if it can still be scored
+    ///
+    /// # Example
+    /// ```rust
+    /// use yahtzee::player::Player;
+    /// use yahtzee::score::{Category, Score};
+    ///
+    /// let mut player = Player::new("test".to_owned());
+    /// player.dice = vec![2, 2, 2, 5, 5];
+    /// assert_eq!(player.possible_score(Category::FullHouse), Some(Score::FullHouse(25)));
+    /// player.update_score(Score::FullHouse(25));
+    /// assert_eq!(player.possible_score(Category::FullHouse), None);
+    /// ```
+    pub fn possible_score(&self, category: Category) -> Option<Score> {
+        self.possible_scores()
+            .into_iter()
+            .find(|score| score.category() == Some(category))
+    }
+
+    /// the score written in a category's box, if it has been scored
+    pub fn scored(&self, category: Category) -> Option<Score> {
+        self.scores
+            .iter()
+            .find(|score| score.category() == Some(category))
+            .copied()
+    }
+
+    /// update player score and scores, adding the upper score bonus as soon
+    /// as it's earned
     ///
     /// # Example
     /// ```rust
@@ -154,31 +223,157 @@ impl Player {
     /// player.update_score(Score::Threes(9));
     /// assert_eq!(player.score, 9);
     /// assert_eq!(player.scores, vec![Score::Threes(9)]);
+    ///
+    /// player.update_score(Score::Sixes(30));
+    /// player.update_score(Score::Fives(25));
+    /// assert_eq!(player.scores.last(), Some(&Score::UpperScoreBonus(35)));
+    /// assert_eq!(player.score, 99);
     /// ```
     pub fn update_score(&mut self, score: Score) {
         self.scores.push(score);
-        self.score = self.score
-            + match score {
-                Score::Aces(score) => score,
-                Score::Twos(score) => score,
-                Score::Threes(score) => score,
-                Score::Fours(score) => score,
-                Score::Fives(score) => score,
-                Score::Sixes(score) => score,
-                Score::UpperScoreBonus(score) => score,
-                Score::ThreeOfAKind(score) => score,
-                Score::FourOfAKind(score) => score,
-                Score::FullHouse(score) => score,
-                Score::SmallStraight(score) => score,
-                Score::LargeStraight(score) => score,
-                Score::Chance(score) => score,
-                Score::Yahtzee(score) => score,
-            } as u32;
+        self.score += score.value();
+
+        let upper = score.category().and_then(Category::face).is_some();
+        if upper && !self.has_upper_bonus() {
+            if let Some(bonus) = Score::upper_score_bonus(self) {
+                self.update_score(bonus);
+            }
+        }
+    }
+}
+
+/// Reasons dice entered by hand can't be used
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum DiceError {
+    WrongCount { expected: usize, found: usize },
+    OutOfRange { die: u32, num_faces: u32 },
+}
+
+impl fmt::Display for DiceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiceError::WrongCount { expected, found } => {
+                write!(f, "expected {} dice but got {}", expected, found)
+            }
+            DiceError::OutOfRange { die, num_faces } => {
+                write!(f, "{} is not between 1 and {}", die, num_faces)
+            }
+        }
+    }
+}
+
+impl Player {
+    /// sets the dice to values rolled outside of the game, such as with real dice
+    ///
+    /// # Example
+    /// ```rust
+    /// use yahtzee::player::{DiceError, Player};
+    ///
+    /// let mut player = Player::new("test".to_owned());
+    /// assert_eq!(player.set_dice(vec![3, 3, 4, 4, 4]), Ok(()));
+    /// assert_eq!(player.dice, vec![3, 3, 4, 4, 4]);
+    /// assert_eq!(
+    ///     player.set_dice(vec![3, 3, 4]),
+    ///     Err(DiceError::WrongCount { expected: 5, found: 3 })
+    /// );
+    /// assert_eq!(
+    ///     player.set_dice(vec![3, 3, 4, 4, 7]),
+    ///     Err(DiceError::OutOfRange { die: 7, num_faces: 6 })
+    /// );
+    /// assert_eq!(player.dice, vec![3, 3, 4, 4, 4]);
+    /// ```
+    pub fn set_dice(&mut self, dice: Vec<u32>) -> Result<(), DiceError> {
+        if dice.len() != self.rules.num_dice {
+            return Err(DiceError::WrongCount {
+                expected: self.rules.num_dice,
+                found: dice.len(),
+            });
+        }
+        if let Some(die) = dice
+            .iter()
+            .find(|die| !(1..=self.rules.num_faces).contains(*die))
+        {
+            return Err(DiceError::OutOfRange {
+                die: *die,
+                num_faces: self.rules.num_faces,
+            });
+        }
+
+        self.dice = dice;
+        Ok(())
     }
 }
 
 impl Player {
+    /// the total of the scores in the upper section boxes, without the bonus
+    ///
+    /// # Example
+    /// ```rust
+    /// use yahtzee::player::Player;
+    /// use yahtzee::score::Score;
+    ///
+    /// let mut player = Player::new("test".to_owned());
+    /// player.update_score(Score::Fours(12));
+    /// player.update_score(Score::Chance(20));
+    /// player.update_score(Score::Sixes(18));
+    /// assert_eq!(player.upper_total(), 30);
+    /// ```
+    pub fn upper_total(&self) -> u32 {
+        self.scores
+            .iter()
+            .filter(|score| score.category().and_then(Category::face).is_some())
+            .map(Score::value)
+            .sum()
+    }
+
+    /// whether the upper score bonus has been earned
+    pub fn has_upper_bonus(&self) -> bool {
+        self.scores.iter().any(Score::is_upper_score_bonus)
+    }
+
+    /// the upper section points still needed to earn the bonus
+    ///
+    /// # Example
+    /// ```rust
+    /// use yahtzee::player::Player;
+    /// use yahtzee::score::Score;
+    ///
+    /// let mut player = Player::new("test".to_owned());
+    /// player.update_score(Score::Fours(16));
+    /// player.update_score(Score::Aces(2));
+    /// assert_eq!(player.upper_bonus_needed(), 45);
+    /// assert_eq!(player.upper_par(), 15);
+    /// ```
+    pub fn upper_bonus_needed(&self) -> u32 {
+        self.rules
+            .upper_bonus_threshold()
+            .saturating_sub(self.upper_total())
+    }
+
+    /// the upper total of three of each face scored so far, which is on
+    /// course for the bonus
+    pub fn upper_par(&self) -> u32 {
+        self.scores
+            .iter()
+            .filter_map(|score| score.category().and_then(Category::face))
+            .map(|face| 3 * face)
+            .sum()
+    }
+
+    /// the number of Yahtzees scored, including bonus ones
+    pub fn yahtzees(&self) -> u32 {
+        self.scores
+            .iter()
+            .filter(|score| matches!(score, Score::Yahtzee(points) if *points > 0))
+            .count() as u32
+    }
+
+    /// adds anything still owed at the end of a game, which is only the upper
+    /// score bonus when scores were recorded some other way
     pub fn endgame(&mut self) {
+        if self.has_upper_bonus() {
+            return;
+        }
         if let Some(score) = Score::upper_score_bonus(self) {
             self.update_score(score);
         }
@@ -0,0 +1,353 @@
+use crate::rules::Rules;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Dice patterns that the probability calculator can aim for.
+///
+/// # Example
+/// ```rust
+/// use yahtzee::probability::Target;
+///
+/// assert!(Target::FullHouse.is_met(&[2, 5, 2, 5, 5]));
+/// assert!(Target::AtLeast { count: 2, face: 5 }.is_met(&[2, 5, 2, 5, 5]));
+/// assert!(!Target::SmallStraight.is_met(&[2, 5, 2, 5, 5]));
+/// ```
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Target {
+    Yahtzee,
+    LargeStraight,
+    SmallStraight,
+    FullHouse,
+    FourOfAKind,
+    ThreeOfAKind,
+    AtLeast { count: u32, face: u32 },
+}
+
+impl Target {
+    /// the targets that correspond to the lower section boxes
+    pub fn standard() -> Vec<Target> {
+        vec![
+            Target::Yahtzee,
+            Target::LargeStraight,
+            Target::SmallStraight,
+            Target::FullHouse,
+            Target::FourOfAKind,
+            Target::ThreeOfAKind,
+        ]
+    }
+
+    /// checks whether the dice satisfy the target
+    pub fn is_met(&self, dice: &[u32]) -> bool {
+        let counts = face_counts(dice);
+        let most_of_a_kind = counts.iter().max().copied().unwrap_or(0);
+
+        match *self {
+            Target::Yahtzee => most_of_a_kind as usize == dice.len(),
+            Target::LargeStraight => longest_run(&counts) as usize >= dice.len(),
+            Target::SmallStraight => longest_run(&counts) as usize + 1 >= dice.len(),
+            Target::FullHouse => {
+                let mut counts = counts.clone();
+                counts.sort_unstable_by(|a, b| b.cmp(a));
+                counts.len() > 1 && counts[0] >= 3 && counts[1] >= 2
+            }
+            Target::FourOfAKind => most_of_a_kind >= 4,
+            Target::ThreeOfAKind => most_of_a_kind >= 3,
+            Target::AtLeast { count, face } => {
+                dice.iter().filter(|die| **die == face).count() as u32 >= count
+            }
+        }
+    }
+}
+
+impl fmt::Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Target::Yahtzee => write!(f, "Yahtzee"),
+            Target::LargeStraight => write!(f, "Large Straight"),
+            Target::SmallStraight => write!(f, "Small Straight"),
+            Target::FullHouse => write!(f, "Full House"),
+            Target::FourOfAKind => write!(f, "Four of a Kind"),
+            Target::ThreeOfAKind => write!(f, "Three of a Kind"),
+            Target::AtLeast { count, face } => write!(f, "At least {} x {}", count, face),
+        }
+    }
+}
+
+/// counts how many of each face appear in the dice, indexed by face - 1
+fn face_counts(dice: &[u32]) -> Vec<u32> {
+    let mut counts = vec![0; dice.iter().max().copied().unwrap_or(0) as usize];
+    for die in dice {
+        counts[*die as usize - 1] += 1;
+    }
+    counts
+}
+
+/// length of the longest run of consecutive faces present in the counts
+fn longest_run(counts: &[u32]) -> u32 {
+    let mut longest = 0;
+    let mut run = 0;
+    for count in counts {
+        if *count > 0 {
+            run += 1;
+            longest = longest.max(run);
+        } else {
+            run = 0;
+        }
+    }
+    longest
+}
+
+fn factorial(n: u32) -> f64 {
+    (1..=n).map(f64::from).product()
+}
+
+/// Every distinct outcome (as a sorted multiset) of rolling `num_dice` of the
+/// rules' dice along with the probability of rolling it.
+///
+/// # Example
+/// ```rust
+/// use yahtzee::probability::roll_distribution;
+/// use yahtzee::rules::Rules;
+///
+/// let outcomes = roll_distribution(&Rules::default(), 2);
+/// assert_eq!(outcomes.len(), 21);
+/// assert!(outcomes.contains(&(vec![3, 5], 2.0 / 36.0)));
+/// ```
+pub fn roll_distribution(rules: &Rules, num_dice: usize) -> Vec<(Vec<u32>, f64)> {
+    let mut outcomes = vec![];
+    let mut dice = Vec::with_capacity(num_dice);
+    collect_multisets(rules.num_faces, num_dice, 1, &mut dice, &mut outcomes);
+
+    let total = f64::from(rules.num_faces).powi(num_dice as i32);
+    outcomes
+        .into_iter()
+        .map(|dice| {
+            let ways = face_counts(&dice)
+                .iter()
+                .fold(factorial(num_dice as u32), |ways, count| {
+                    ways / factorial(*count)
+                });
+            (dice, ways / total)
+        })
+        .collect()
+}
+
+fn collect_multisets(
+    num_faces: u32,
+    remaining: usize,
+    lowest_face: u32,
+    dice: &mut Vec<u32>,
+    outcomes: &mut Vec<Vec<u32>>,
+) {
+    if remaining == 0 {
+        outcomes.push(dice.clone());
+        return;
+    }
+    for face in lowest_face..=num_faces {
+        dice.push(face);
+        collect_multisets(num_faces, remaining - 1, face, dice, outcomes);
+        dice.pop();
+    }
+}
+
+/// The distribution of hands after keeping `held` dice and rerolling the rest.
+///
+/// # Example
+/// ```rust
+/// use yahtzee::probability::reroll_distribution;
+/// use yahtzee::rules::Rules;
+///
+/// let dice = [6, 6, 6, 6, 2];
+/// let held = [true, true, true, true, false];
+/// let outcomes = reroll_distribution(&Rules::default(), &dice, &held);
+/// assert_eq!(outcomes.len(), 6);
+/// assert!(outcomes.contains(&(vec![6, 6, 6, 6, 6], 1.0 / 6.0)));
+/// ```
+pub fn reroll_distribution(rules: &Rules, dice: &[u32], held: &[bool]) -> Vec<(Vec<u32>, f64)> {
+    let kept = kept_dice(dice, held);
+    roll_distribution(rules, dice.len() - kept.len())
+        .into_iter()
+        .map(|(rolled, probability)| (merge(&kept, &rolled), probability))
+        .collect()
+}
+
+fn kept_dice(dice: &[u32], held: &[bool]) -> Vec<u32> {
+    let mut kept: Vec<u32> = dice
+        .iter()
+        .zip(held.iter())
+        .filter(|(_, held)| **held)
+        .map(|(die, _)| *die)
+        .collect();
+    kept.sort_unstable();
+    kept
+}
+
+fn merge(kept: &[u32], rolled: &[u32]) -> Vec<u32> {
+    let mut dice = [kept, rolled].concat();
+    dice.sort_unstable();
+    dice
+}
+
+/// Exact probability of finishing the turn with dice meeting the target.
+///
+/// The `held` dice are kept and the rest are rerolled, using up one of the
+/// `rolls_remaining`. Any rolls left after that are played with whichever
+/// holds give the best chance of reaching the target. With no rolls remaining
+/// the result is simply whether the current dice already meet the target.
+///
+/// # Example
+/// ```rust
+/// use yahtzee::probability::{probability, Target};
+/// use yahtzee::rules::Rules;
+///
+/// let rules = Rules::default();
+/// let dice = [6, 6, 6, 6, 2];
+/// let held = [true, true, true, true, false];
+/// let one_roll = probability(&rules, &dice, &held, 1, Target::Yahtzee);
+/// assert!((one_roll - 1.0 / 6.0).abs() < 1e-12);
+///
+/// let two_rolls = probability(&rules, &dice, &held, 2, Target::Yahtzee);
+/// assert!((two_rolls - 11.0 / 36.0).abs() < 1e-12);
+/// ```
+pub fn probability(
+    rules: &Rules,
+    dice: &[u32],
+    held: &[bool],
+    rolls_remaining: u32,
+    target: Target,
+) -> f64 {
+    if rolls_remaining == 0 {
+        return if target.is_met(dice) { 1.0 } else { 0.0 };
+    }
+
+    let mut calculator = Calculator::new(rules, target);
+    calculator.keep_probability(&kept_dice(dice, held), rolls_remaining)
+}
+
+/// Exact probability of reaching the target when every remaining hold,
+/// including the one about to be made, is chosen to favour the target.
+///
+/// # Example
+/// ```rust
+/// use yahtzee::probability::{best_probability, Target};
+/// use yahtzee::rules::Rules;
+///
+/// let rules = Rules::default();
+/// assert_eq!(best_probability(&rules, &[1, 2, 3, 4, 5], 2, Target::LargeStraight), 1.0);
+/// ```
+pub fn best_probability(rules: &Rules, dice: &[u32], rolls_remaining: u32, target: Target) -> f64 {
+    let mut calculator = Calculator::new(rules, target);
+    let mut sorted = dice.to_vec();
+    sorted.sort_unstable();
+    calculator.hand_probability(&sorted, rolls_remaining)
+}
+
+/// memoizes the probability of reaching a target from hands and kept dice
+struct Calculator {
+    target: Target,
+    num_dice: usize,
+    outcomes: Vec<Vec<(Vec<u32>, f64)>>,
+    hands: HashMap<(Vec<u32>, u32), f64>,
+    keeps: HashMap<(Vec<u32>, u32), f64>,
+}
+
+impl Calculator {
+    fn new(rules: &Rules, target: Target) -> Calculator {
+        Calculator {
+            target,
+            num_dice: rules.num_dice,
+            outcomes: (0..=rules.num_dice)
+                .map(|num_dice| roll_distribution(rules, num_dice))
+                .collect(),
+            hands: HashMap::new(),
+            keeps: HashMap::new(),
+        }
+    }
+
+    /// probability from a sorted hand with the decision of what to hold still to make
+    fn hand_probability(&mut self, hand: &[u32], rolls_remaining: u32) -> f64 {
+        if self.target.is_met(hand) {
+            return 1.0;
+        }
+        if rolls_remaining == 0 {
+            return 0.0;
+        }
+        if let Some(probability) = self.hands.get(&(hand.to_vec(), rolls_remaining)) {
+            return *probability;
+        }
+
+        let mut best: f64 = 0.0;
+        for mask in 0..(1u32 << hand.len()) {
+            let kept: Vec<u32> = hand
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| mask & (1 << i) != 0)
+                .map(|(_, die)| *die)
+                .collect();
+            best = best.max(self.keep_probability(&kept, rolls_remaining));
+        }
+
+        self.hands.insert((hand.to_vec(), rolls_remaining), best);
+        best
+    }
+
+    /// probability after holding the sorted `kept` dice and rerolling the rest
+    fn keep_probability(&mut self, kept: &[u32], rolls_remaining: u32) -> f64 {
+        if let Some(probability) = self.keeps.get(&(kept.to_vec(), rolls_remaining)) {
+            return *probability;
+        }
+
+        let outcomes = self.outcomes[self.num_dice - kept.len()].clone();
+        let probability = outcomes
+            .iter()
+            .map(|(rolled, chance)| {
+                chance * self.hand_probability(&merge(kept, rolled), rolls_remaining - 1)
+            })
+            .sum();
+
+        self.keeps
+            .insert((kept.to_vec(), rolls_remaining), probability);
+        probability
+    }
+}
+
+/// the chance that a normally distributed amount ends up above zero, for
+/// amounts that are really whole numbers, counting exactly zero as half
+pub(crate) fn chance_above(mean: f64, variance: f64) -> f64 {
+    if variance <= 0.0 {
+        return match mean {
+            mean if mean.abs() < 0.5 => 0.5,
+            mean if mean > 0.0 => 1.0,
+            _ => 0.0,
+        };
+    }
+    0.5 * erfc(-mean / (2.0 * variance).sqrt())
+}
+
+/// the complementary error function, accurate relative to its value even
+/// far out in the tail (Numerical Recipes' erfcc)
+pub(crate) fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let polynomial = [
+        -1.265_512_23,
+        1.000_023_68,
+        0.374_091_96,
+        0.096_784_18,
+        -0.186_288_06,
+        0.278_868_07,
+        -1.135_203_98,
+        1.488_515_87,
+        -0.822_152_23,
+        0.170_872_77,
+    ]
+    .iter()
+    .rev()
+    .fold(0.0, |sum, coefficient| coefficient + t * sum);
+    let tail = t * (-z * z + polynomial).exp();
+    if x >= 0.0 {
+        tail
+    } else {
+        2.0 - tail
+    }
+}
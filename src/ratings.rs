@@ -0,0 +1,189 @@
+use crate::daily::Date;
+use crate::standings::Standing;
+use crate::storage;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io;
+use std::path::Path;
+
+/// file in the data directory that holds everyone's ratings
+pub const RATINGS_FILE: &str = "ratings.json";
+
+/// the rating a player starts from
+pub const INITIAL_RATING: f64 = 1500.0;
+
+/// the most a rating can move in one game
+pub const K_FACTOR: f64 = 32.0;
+
+/// The chance a player is expected to finish ahead of another, from their
+/// ratings.
+///
+/// # Example
+/// ```rust
+/// use yahtzee::ratings;
+///
+/// assert_eq!(ratings::expected_score(1500.0, 1500.0), 0.5);
+/// assert!((ratings::expected_score(1600.0, 1200.0) - 0.909).abs() < 0.001);
+/// ```
+pub fn expected_score(rating: f64, other: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf((other - rating) / 400.0))
+}
+
+/// How one game changed a player's rating
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct RatingChange {
+    pub date: Date,
+    /// the rating after the game
+    pub rating: f64,
+    pub change: f64,
+    pub place: usize,
+    /// how many rated players were in the game
+    pub players: usize,
+}
+
+/// A player's rating and how it got there
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct PlayerRating {
+    pub rating: f64,
+    pub games: u32,
+    /// every change, oldest first
+    pub history: Vec<RatingChange>,
+}
+
+impl Default for PlayerRating {
+    fn default() -> PlayerRating {
+        PlayerRating {
+            rating: INITIAL_RATING,
+            games: 0,
+            history: Vec::new(),
+        }
+    }
+}
+
+/// Everyone's ratings, keyed by player name and stored as JSON.
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct RatingStore {
+    pub players: BTreeMap<String, PlayerRating>,
+}
+
+impl RatingStore {
+    /// reads the ratings saved in a file, with nobody rated yet if nothing
+    /// has been saved
+    pub fn load(path: &Path) -> io::Result<RatingStore> {
+        storage::load_json(path)
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        storage::save_json(path, self)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&PlayerRating> {
+        self.players.get(name)
+    }
+
+    /// Updates everyone's rating from where they finished a game, returning
+    /// the changes in the order of the standings.
+    ///
+    /// Head-to-head games are Elo. A table of three or more counts as a game
+    /// against each other player, finishing ahead, level or behind them, with
+    /// the changes scaled down so a game moves a rating as much whatever the
+    /// number of players. Games with one player don't change anything.
+    ///
+    /// # Example
+    /// ```rust
+    /// use yahtzee::daily::Date;
+    /// use yahtzee::ratings::RatingStore;
+    /// use yahtzee::standings::Standing;
+    ///
+    /// let standing = |place, name: &str| Standing { place, name: name.to_owned(), score: 0, behind: 0 };
+    /// let date = Date { year: 2024, month: 5, day: 1 };
+    /// let mut store = RatingStore::default();
+    ///
+    /// let changes = store.record_game(&[standing(1, "Ann"), standing(2, "Bob")], date);
+    /// assert_eq!(changes[0].change, 16.0);
+    /// assert_eq!(store.get("Bob").unwrap().rating, 1484.0);
+    ///
+    /// // beating higher rated players is worth more, and losing to lower
+    /// // rated ones costs more
+    /// let changes = store.record_game(&[standing(1, "Bob"), standing(2, "Cat"), standing(2, "Ann")], date);
+    /// assert!(changes[0].change > 16.0);
+    /// assert!(changes[2].change < changes[1].change);
+    /// assert_eq!(store.get("Ann").unwrap().history.len(), 2);
+    /// let total: f64 = store.players.values().map(|player| player.rating - 1500.0).sum();
+    /// assert!(total.abs() < 1e-9);
+    /// ```
+    pub fn record_game(&mut self, standings: &[Standing], date: Date) -> Vec<RatingChange> {
+        if standings.len() < 2 {
+            return Vec::new();
+        }
+        let ratings: Vec<f64> = standings
+            .iter()
+            .map(|standing| {
+                self.get(&standing.name)
+                    .map_or(INITIAL_RATING, |player| player.rating)
+            })
+            .collect();
+        let opponents = (standings.len() - 1) as f64;
+
+        let mut changes = Vec::with_capacity(standings.len());
+        for (i, standing) in standings.iter().enumerate() {
+            let surplus: f64 = standings
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .map(|(j, other)| {
+                    let actual = match standing.place.cmp(&other.place) {
+                        std::cmp::Ordering::Less => 1.0,
+                        std::cmp::Ordering::Equal => 0.5,
+                        std::cmp::Ordering::Greater => 0.0,
+                    };
+                    actual - expected_score(ratings[i], ratings[j])
+                })
+                .sum();
+            let change = K_FACTOR * surplus / opponents;
+
+            let player = self.players.entry(standing.name.clone()).or_default();
+            player.rating += change;
+            player.games += 1;
+            let recorded = RatingChange {
+                date,
+                rating: player.rating,
+                change,
+                place: standing.place,
+                players: standings.len(),
+            };
+            player.history.push(recorded.clone());
+            changes.push(recorded);
+        }
+        changes
+    }
+
+    /// everyone rated, the highest rating first
+    pub fn leaderboard(&self) -> Vec<(&str, &PlayerRating)> {
+        let mut leaderboard: Vec<(&str, &PlayerRating)> = self
+            .players
+            .iter()
+            .map(|(name, player)| (name.as_str(), player))
+            .collect();
+        leaderboard.sort_by(|a, b| b.1.rating.total_cmp(&a.1.rating));
+        leaderboard
+    }
+}
+
+/// Records a finished game in the ratings file in the data directory.
+pub fn record_game(standings: &[Standing], date: Date) -> io::Result<Vec<RatingChange>> {
+    if standings.len() < 2 {
+        return Ok(Vec::new());
+    }
+
+    let path = storage::data_file(RATINGS_FILE)?;
+    let mut store = RatingStore::load(&path)?;
+    let changes = store.record_game(standings, date);
+    store.save(&path)?;
+    Ok(changes)
+}
+
+/// Reads the ratings file in the data directory.
+pub fn load() -> io::Result<RatingStore> {
+    RatingStore::load(&storage::data_file(RATINGS_FILE)?)
+}
@@ -0,0 +1,100 @@
+use crate::score::Category;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+pub const MIN_DICE: usize = 5;
+pub const MAX_DICE: usize = 8;
+pub const MIN_FACES: u32 = 4;
+pub const MAX_FACES: u32 = 8;
+
+/// The shape of the dice a game is played with.
+///
+/// Straights are measured against the number of dice: a large straight is a
+/// run through every die and a small straight a run through all but one.
+/// The upper section has a box for every face and its bonus is earned for
+/// scoring three of each face on average.
+///
+/// # Example
+/// ```rust
+/// use yahtzee::rules::Rules;
+///
+/// let rules = Rules::new(6, 8).unwrap();
+/// assert_eq!(rules.upper_bonus_threshold(), 108);
+/// assert_eq!(rules.num_rounds(), 15);
+/// assert_eq!(Rules::default().upper_bonus_threshold(), 63);
+/// assert!(Rules::new(3, 6).is_err());
+/// ```
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
+pub struct Rules {
+    pub num_dice: usize,
+    pub num_faces: u32,
+}
+
+/// Reasons a set of rules can't be played
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum RulesError {
+    DiceCount(usize),
+    FaceCount(u32),
+}
+
+impl Rules {
+    pub fn new(num_dice: usize, num_faces: u32) -> Result<Rules, RulesError> {
+        if !(MIN_DICE..=MAX_DICE).contains(&num_dice) {
+            return Err(RulesError::DiceCount(num_dice));
+        }
+        if !(MIN_FACES..=MAX_FACES).contains(&num_faces) {
+            return Err(RulesError::FaceCount(num_faces));
+        }
+        Ok(Rules {
+            num_dice,
+            num_faces,
+        })
+    }
+
+    /// whether these are the rules of a regular game of five six-sided dice
+    pub fn is_standard(&self) -> bool {
+        *self == Rules::default()
+    }
+
+    /// one round is played for every box on the scorecard
+    pub fn num_rounds(&self) -> usize {
+        Category::for_rules(self).len()
+    }
+
+    /// the upper section total needed to earn the upper score bonus
+    pub fn upper_bonus_threshold(&self) -> u32 {
+        3 * (1..=self.num_faces).sum::<u32>()
+    }
+}
+
+impl Default for Rules {
+    fn default() -> Rules {
+        Rules {
+            num_dice: 5,
+            num_faces: 6,
+        }
+    }
+}
+
+impl fmt::Display for Rules {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}d{}", self.num_dice, self.num_faces)
+    }
+}
+
+impl fmt::Display for RulesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RulesError::DiceCount(count) => write!(
+                f,
+                "{} dice is not supported, use between {} and {}",
+                count, MIN_DICE, MAX_DICE
+            ),
+            RulesError::FaceCount(count) => write!(
+                f,
+                "{}-sided dice are not supported, use between {} and {} faces",
+                count, MIN_FACES, MAX_FACES
+            ),
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use crate::player::Player;
+use crate::rules::Rules;
 use derive_is_enum_variant::is_enum_variant;
-use std::fmt;
+use serde::{Deserialize, Serialize};
+use std::{fmt, str};
 
 /// Holds the different types of scores that are possible in a Yahtzee game.
 ///
@@ -11,7 +13,7 @@ use std::fmt;
 /// let score_value = 50;
 /// let yahtzee = Score::Yahtzee(score_value);
 /// ```
-#[derive(Debug, is_enum_variant, PartialEq, Clone, Copy)]
+#[derive(Debug, is_enum_variant, PartialEq, Clone, Copy, Serialize, Deserialize)]
 pub enum Score {
     Aces(u32),
     Twos(u32),
@@ -19,6 +21,8 @@ pub enum Score {
     Fours(u32),
     Fives(u32),
     Sixes(u32),
+    Sevens(u32),
+    Eights(u32),
     ThreeOfAKind(u32),
     FourOfAKind(u32),
     FullHouse(u32),
@@ -29,40 +33,294 @@ pub enum Score {
     UpperScoreBonus(u32),
 }
 
+/// The thirteen boxes on a scorecard, without a score value attached.
+///
+/// # Example
+/// ```rust
+/// use yahtzee::score::{Category, Score};
+///
+/// assert_eq!(Category::FullHouse.score(25), Score::FullHouse(25));
+/// assert_eq!(Score::Fives(15).category(), Some(Category::Fives));
+/// assert_eq!(Score::UpperScoreBonus(35).category(), None);
+/// ```
+#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Clone, Copy, Serialize, Deserialize)]
+pub enum Category {
+    Aces,
+    Twos,
+    Threes,
+    Fours,
+    Fives,
+    Sixes,
+    ThreeOfAKind,
+    FourOfAKind,
+    FullHouse,
+    SmallStraight,
+    LargeStraight,
+    Chance,
+    Yahtzee,
+    Sevens,
+    Eights,
+}
+
+impl Category {
+    /// the boxes of a regular game, in index order, which are all but the
+    /// upper boxes for dice with more than six faces
+    ///
+    /// Those boxes come after these so the regular boxes keep the same index
+    /// whatever the rules.
+    pub const STANDARD: [Category; 13] = [
+        Category::Aces,
+        Category::Twos,
+        Category::Threes,
+        Category::Fours,
+        Category::Fives,
+        Category::Sixes,
+        Category::ThreeOfAKind,
+        Category::FourOfAKind,
+        Category::FullHouse,
+        Category::SmallStraight,
+        Category::LargeStraight,
+        Category::Chance,
+        Category::Yahtzee,
+    ];
+
+    /// number of categories, including those for dice with more than six
+    /// faces, which is more than `Category::STANDARD` holds
+    pub const COUNT: usize = 15;
+
+    /// position of the category in `Category::STANDARD`, or past the end for
+    /// the boxes only used with more than six faces
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    /// the upper section box for a die face
+    pub fn upper(die_face: u32) -> Option<Category> {
+        match die_face {
+            1 => Some(Category::Aces),
+            2 => Some(Category::Twos),
+            3 => Some(Category::Threes),
+            4 => Some(Category::Fours),
+            5 => Some(Category::Fives),
+            6 => Some(Category::Sixes),
+            7 => Some(Category::Sevens),
+            8 => Some(Category::Eights),
+            _ => None,
+        }
+    }
+
+    /// the die face counted by an upper section box
+    pub fn face(self) -> Option<u32> {
+        (1..=8).find(|die_face| Category::upper(*die_face) == Some(self))
+    }
+
+    /// the boxes on the scorecard for a set of rules
+    ///
+    /// # Example
+    /// ```rust
+    /// use yahtzee::rules::Rules;
+    /// use yahtzee::score::Category;
+    ///
+    /// assert_eq!(Category::for_rules(&Rules::default()), Category::STANDARD.to_vec());
+    /// assert_eq!(Category::for_rules(&Rules::new(5, 8).unwrap()).len(), Category::COUNT);
+    /// ```
+    pub fn for_rules(rules: &Rules) -> Vec<Category> {
+        let mut categories: Vec<Category> = (1..=rules.num_faces.min(6))
+            .filter_map(Category::upper)
+            .collect();
+        categories.extend_from_slice(&Category::STANDARD[6..]);
+        categories.extend((7..=rules.num_faces).filter_map(Category::upper));
+        categories
+    }
+
+    /// attach a score value to the category
+    pub fn score(self, points: u32) -> Score {
+        match self {
+            Category::Aces => Score::Aces(points),
+            Category::Twos => Score::Twos(points),
+            Category::Threes => Score::Threes(points),
+            Category::Fours => Score::Fours(points),
+            Category::Fives => Score::Fives(points),
+            Category::Sixes => Score::Sixes(points),
+            Category::Sevens => Score::Sevens(points),
+            Category::Eights => Score::Eights(points),
+            Category::ThreeOfAKind => Score::ThreeOfAKind(points),
+            Category::FourOfAKind => Score::FourOfAKind(points),
+            Category::FullHouse => Score::FullHouse(points),
+            Category::SmallStraight => Score::SmallStraight(points),
+            Category::LargeStraight => Score::LargeStraight(points),
+            Category::Chance => Score::Chance(points),
+            Category::Yahtzee => Score::Yahtzee(points),
+        }
+    }
+}
+
+/// Error for text that doesn't name a category
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ParseCategoryError(String);
+
+impl fmt::Display for ParseCategoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "\"{}\" is not a category name or code", self.0)
+    }
+}
+
+impl Category {
+    /// the short code a category can be picked with
+    pub fn code(self) -> &'static str {
+        match self {
+            Category::Aces => "1",
+            Category::Twos => "2",
+            Category::Threes => "3",
+            Category::Fours => "4",
+            Category::Fives => "5",
+            Category::Sixes => "6",
+            Category::Sevens => "7",
+            Category::Eights => "8",
+            Category::ThreeOfAKind => "3k",
+            Category::FourOfAKind => "4k",
+            Category::FullHouse => "fh",
+            Category::SmallStraight => "ss",
+            Category::LargeStraight => "ls",
+            Category::Chance => "ch",
+            Category::Yahtzee => "yz",
+        }
+    }
+
+    /// every category, including those for dice with more than six faces
+    pub fn all() -> impl Iterator<Item = Category> {
+        Category::STANDARD
+            .iter()
+            .copied()
+            .chain([Category::Sevens, Category::Eights])
+    }
+}
+
+impl str::FromStr for Category {
+    type Err = ParseCategoryError;
+
+    /// Parses a category from its name or short code, ignoring case, spaces,
+    /// hyphens and underscores.
+    ///
+    /// # Example
+    /// ```rust
+    /// use yahtzee::score::Category;
+    ///
+    /// assert_eq!("fh".parse(), Ok(Category::FullHouse));
+    /// assert_eq!("Full House".parse(), Ok(Category::FullHouse));
+    /// assert_eq!("3k".parse(), Ok(Category::ThreeOfAKind));
+    /// assert_eq!("6".parse(), Ok(Category::Sixes));
+    /// assert!("house".parse::<Category>().is_err());
+    /// ```
+    fn from_str(input: &str) -> Result<Category, ParseCategoryError> {
+        let normalized: String = input
+            .chars()
+            .filter(|c| !c.is_whitespace() && *c != '-' && *c != '_')
+            .flat_map(char::to_lowercase)
+            .collect();
+
+        Category::all()
+            .find(|category| {
+                let name = category.to_string().replace(' ', "").to_lowercase();
+                normalized == category.code() || normalized == name
+            })
+            .or(match normalized.as_str() {
+                "ones" => Some(Category::Aces),
+                _ => None,
+            })
+            .ok_or_else(|| ParseCategoryError(input.trim().to_owned()))
+    }
+}
+
+impl Score {
+    /// the scorecard box the score was written in, if it is one
+    pub fn category(&self) -> Option<Category> {
+        match self {
+            Score::Aces(_) => Some(Category::Aces),
+            Score::Twos(_) => Some(Category::Twos),
+            Score::Threes(_) => Some(Category::Threes),
+            Score::Fours(_) => Some(Category::Fours),
+            Score::Fives(_) => Some(Category::Fives),
+            Score::Sixes(_) => Some(Category::Sixes),
+            Score::Sevens(_) => Some(Category::Sevens),
+            Score::Eights(_) => Some(Category::Eights),
+            Score::ThreeOfAKind(_) => Some(Category::ThreeOfAKind),
+            Score::FourOfAKind(_) => Some(Category::FourOfAKind),
+            Score::FullHouse(_) => Some(Category::FullHouse),
+            Score::SmallStraight(_) => Some(Category::SmallStraight),
+            Score::LargeStraight(_) => Some(Category::LargeStraight),
+            Score::Chance(_) => Some(Category::Chance),
+            Score::Yahtzee(_) => Some(Category::Yahtzee),
+            Score::UpperScoreBonus(_) => None,
+        }
+    }
+
+    /// the points the score is worth
+    pub fn value(&self) -> u32 {
+        match *self {
+            Score::Aces(score)
+            | Score::Twos(score)
+            | Score::Threes(score)
+            | Score::Fours(score)
+            | Score::Fives(score)
+            | Score::Sixes(score)
+            | Score::Sevens(score)
+            | Score::Eights(score)
+            | Score::UpperScoreBonus(score)
+            | Score::ThreeOfAKind(score)
+            | Score::FourOfAKind(score)
+            | Score::FullHouse(score)
+            | Score::SmallStraight(score)
+            | Score::LargeStraight(score)
+            | Score::Chance(score)
+            | Score::Yahtzee(score) => score,
+        }
+    }
+}
+
 /// Methods to check for valid scores
 impl Score {
     /// Find yahtzee and return it if found in the dice
     ///
+    /// The Yahtzee box can only be filled once, except that rolling another
+    /// yahtzee after scoring one for 50 can be scored again for 150.
+    ///
     /// # Example
     /// ```rust
     /// use yahtzee::player::Player;
     /// use yahtzee::score::Score;
     ///
     /// let mut player = Player::new("test".to_owned());
-    /// player.dice = [6;5];
-    /// if let Some(first_yahtzee) = Score::find_yahtzee(&player) {
+    /// player.dice = vec![6; 5];
+    /// if let Some(first_yahtzee) = Score::yahtzee(&player) {
     ///     assert_eq!(first_yahtzee, Score::Yahtzee(50));
     /// } else {
     ///     assert!(false);
     /// }
+    ///
+    /// player.update_score(Score::Yahtzee(50));
+    /// assert_eq!(Score::yahtzee(&player), Some(Score::Yahtzee(150)));
+    /// player.dice = vec![6, 6, 6, 6, 1];
+    /// assert_eq!(Score::yahtzee(&player), None);
     /// ```
     pub fn yahtzee(player: &Player) -> Option<Score> {
+        let mut is_yahtzee = true;
         for i in 1..player.dice.len() {
             if player.dice[0] != player.dice[i] {
-                return Some(Score::Yahtzee(0));
+                is_yahtzee = false;
             }
         }
-        // bonus points for already scoring yahtzee,
-        // but only if the score was not for 0
-        for player_score in player.scores.iter() {
-            if let Score::Yahtzee(score_value) = player_score {
-                if *score_value != 0 {
-                    return Some(Score::Yahtzee(150));
-                }
+
+        match player.scores.iter().find(|score| score.is_yahtzee()) {
+            None if is_yahtzee => Some(Score::Yahtzee(50)),
+            None => Some(Score::Yahtzee(0)),
+            // bonus points for already scoring yahtzee,
+            // but only if the score was not for 0
+            Some(Score::Yahtzee(score_value)) if *score_value != 0 && is_yahtzee => {
+                Some(Score::Yahtzee(150))
             }
+            Some(_) => None,
         }
-
-        Some(Score::Yahtzee(50))
     }
 
     /// Find an upper score for a die value
@@ -78,17 +336,21 @@ impl Score {
     /// use yahtzee::score::Score;
     ///
     /// let mut player = Player::new("test".to_owned());
-    /// player.dice = [1,1,2,2,2];
-    /// if let Some(aces) = Score::find_upper_score(&player, 1) {
+    /// player.dice = vec![1,1,2,2,2];
+    /// if let Some(aces) = Score::upper_score(&player, 1) {
     ///     assert_eq!(aces, Score::Aces(2));
     /// } else {
     ///     assert!(false);
     /// }
     pub fn upper_score(player: &Player, die_face: u32) -> Option<Score> {
+        if die_face > player.rules.num_faces {
+            return None;
+        }
+
         let mut count = 0;
         for die in player.dice.iter() {
             if *die == die_face {
-                count = count + 1;
+                count += 1;
             }
         }
 
@@ -135,11 +397,26 @@ impl Score {
                     Some(Score::Sixes(count * die_face))
                 }
             }
+            7 => {
+                if player.scores.iter().any(|score| score.is_sevens()) {
+                    None
+                } else {
+                    Some(Score::Sevens(count * die_face))
+                }
+            }
+            8 => {
+                if player.scores.iter().any(|score| score.is_eights()) {
+                    None
+                } else {
+                    Some(Score::Eights(count * die_face))
+                }
+            }
             _ => None,
         }
     }
 
-    /// returns the score bonus (35) if the total of upper scores is 63 or more.
+    /// returns the score bonus (35) if the total of upper scores is 63 or more,
+    /// or three of each face for dice that aren't six-sided.
     ///
     /// ```rust
     /// use yahtzee::player::Player;
@@ -155,26 +432,14 @@ impl Score {
     /// }
     /// ```
     pub fn upper_score_bonus(player: &Player) -> Option<Score> {
-        let mut upper_score_total = 0;
-        for score in player.scores.iter() {
-            match score {
-                Score::Aces(score)
-                | Score::Twos(score)
-                | Score::Threes(score)
-                | Score::Fours(score)
-                | Score::Fives(score)
-                | Score::Sixes(score) => upper_score_total = upper_score_total + score,
-                _ => (),
-            }
-        }
-        if upper_score_total >= 63 {
+        if player.upper_total() >= player.rules.upper_bonus_threshold() {
             Some(Score::UpperScoreBonus(35))
         } else {
             None
         }
     }
 
-    /// Find a large straight: a 5 dice sequence of consecutive values
+    /// Find a large straight: every die in a sequence of consecutive values
     ///
     /// # Example
     /// ```rust
@@ -182,8 +447,8 @@ impl Score {
     /// use yahtzee::score::Score;
     ///
     /// let mut player = Player::new("test".to_owned());
-    /// player.dice = [1,2,3,4,5];
-    /// if let Some(large_straight) = Score::find_large_straight(&player) {
+    /// player.dice = vec![1,2,3,4,5];
+    /// if let Some(large_straight) = Score::large_straight(&player) {
     ///     assert_eq!(large_straight, Score::LargeStraight(40));
     /// } else {
     ///     assert!(false);
@@ -193,7 +458,7 @@ impl Score {
             return None;
         }
 
-        let mut dice = player.dice;
+        let mut dice = player.dice.clone();
         dice.sort();
         for i in 0..dice.len() - 1 {
             if dice[i] + 1 != dice[i + 1] {
@@ -204,7 +469,8 @@ impl Score {
         Some(Score::LargeStraight(40))
     }
 
-    /// Find a small straight: a 4 dice sequence of consecutive values.
+    /// Find a small straight: all but one of the dice in a sequence of
+    /// consecutive values.
     ///
     /// # Example
     /// ```rust
@@ -212,8 +478,8 @@ impl Score {
     /// use yahtzee::score::Score;
     ///
     /// let mut player = Player::new("test".to_owned());
-    /// player.dice = [3,2,4,1,6];
-    /// if let Some(small_straight) = Score::find_small_straight(&player) {
+    /// player.dice = vec![3,2,4,1,6];
+    /// if let Some(small_straight) = Score::small_straight(&player) {
     ///     assert_eq!(small_straight, Score::SmallStraight(30));
     /// } else {
     ///     assert!(false);
@@ -223,19 +489,19 @@ impl Score {
             return None;
         }
 
-        let mut dice = player.dice;
+        let mut dice = player.dice.clone();
         dice.sort();
         let mut comparison_correct_count = 0; // what's a good name for you?
         for i in 0..dice.len() - 1 {
             if dice[i] + 1 == dice[i + 1] {
-                comparison_correct_count = comparison_correct_count + 1;
+                comparison_correct_count += 1;
             } else if dice[i] == dice[i + 1] {
                 continue;
             } else {
                 comparison_correct_count = 0;
             }
 
-            if comparison_correct_count == 3 {
+            if comparison_correct_count == dice.len() - 2 {
                 return Some(Score::SmallStraight(30));
             }
         }
@@ -251,7 +517,7 @@ impl Score {
     /// use yahtzee::player::Player;
     ///
     /// let mut player = Player::new("test".to_owned());
-    /// player.dice = [1,1,1,3,6];
+    /// player.dice = vec![1,1,1,3,6];
     /// if let Some(score) = Score::three_of_a_kind(&player) {
     ///     assert_eq!(score, Score::ThreeOfAKind(12)); // 12 is the total of all die faces
     /// } else {
@@ -263,12 +529,12 @@ impl Score {
         }
 
         let mut score = 0;
-        for die_face in 1..=6 {
+        for die_face in 1..=player.rules.num_faces {
             let mut count = 0;
 
             for die in player.dice.iter() {
                 if *die == die_face {
-                    count = count + 1;
+                    count += 1;
                 }
                 if count >= 3 {
                     score = player.dice.iter().sum();
@@ -286,7 +552,7 @@ impl Score {
     /// use yahtzee::player::Player;
     ///
     /// let mut player = Player::new("test".to_owned());
-    /// player.dice = [1,1,1,1,6];
+    /// player.dice = vec![1,1,1,1,6];
     /// if let Some(score) = Score::four_of_a_kind(&player) {
     ///     assert_eq!(score, Score::FourOfAKind(10)); // 10 is the total of all die faces
     /// } else {
@@ -298,12 +564,12 @@ impl Score {
         }
 
         let mut score = 0;
-        for die_face in 1..=6 {
+        for die_face in 1..=player.rules.num_faces {
             let mut count = 0;
 
             for die in player.dice.iter() {
                 if *die == die_face {
-                    count = count + 1;
+                    count += 1;
                 }
                 if count >= 4 {
                     score = player.dice.iter().sum();
@@ -321,7 +587,7 @@ impl Score {
     /// use yahtzee::player::Player;
     ///
     /// let mut player = Player::new("test".to_owned());
-    /// player.dice = [1,1,2,2,2];
+    /// player.dice = vec![1,1,2,2,2];
     /// if let Some(score) = Score::full_house(&player) {
     ///     assert_eq!(score, Score::FullHouse(25));
     /// } else {
@@ -329,37 +595,19 @@ impl Score {
     /// }
     /// ```
     pub fn full_house(player: &Player) -> Option<Score> {
-        if player.scores.iter().any(|score| score.is_four_of_a_kind()) {
+        if player.scores.iter().any(|score| score.is_full_house()) {
             return None;
         }
 
-        let mut three_pair = false;
-        let mut two_pair = false;
-        for die_face in 1..=6 {
-            let mut die_count = 0;
-            for die in player.dice.iter() {
-                if *die == die_face {
-                    die_count = die_count + 1;
-                }
-            }
+        // a full house is three or more of one face with a pair or more of
+        // another, so five of a kind doesn't count
+        let mut die_counts: Vec<usize> = (1..=player.rules.num_faces)
+            .map(|die_face| player.dice.iter().filter(|die| **die == die_face).count())
+            .collect();
+        die_counts.sort_unstable_by(|a, b| b.cmp(a));
 
-            // not possible to have a full house when there are more than 3
-            // of the same die face
-            if die_count > 3 {
-                return Some(Score::FullHouse(0));
-            }
-
-            if !three_pair {
-                three_pair = die_count == 3;
-            }
-
-            if !two_pair {
-                two_pair = die_count == 2;
-            }
-
-            if three_pair && two_pair {
-                return Some(Score::FullHouse(25));
-            }
+        if die_counts[0] >= 3 && die_counts[1] >= 2 {
+            return Some(Score::FullHouse(25));
         }
         Some(Score::FullHouse(0))
     }
@@ -380,7 +628,7 @@ impl fmt::Display for Score {
     /// ```rust
     /// use yahtzee::score::Score;
     /// let twos = Score::Twos(4);
-    /// assert_eq!(format!("{}", twos),"Twos: 4");
+    /// assert_eq!(format!("{}", twos),"Twos: 4 points");
     /// ```
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -390,6 +638,8 @@ impl fmt::Display for Score {
             Score::Fours(score) => write!(f, "Fours: {} points", score),
             Score::Fives(score) => write!(f, "Fives: {} points", score),
             Score::Sixes(score) => write!(f, "Sixes: {} points", score),
+            Score::Sevens(score) => write!(f, "Sevens: {} points", score),
+            Score::Eights(score) => write!(f, "Eights: {} points", score),
             Score::UpperScoreBonus(score) => write!(f, "Upper Score Bonus! {} points", score),
             Score::ThreeOfAKind(score) => write!(f, "Three of a Kind: {} points", score),
             Score::FourOfAKind(score) => write!(f, "Four of a Kind: {} points", score),
@@ -401,3 +651,33 @@ impl fmt::Display for Score {
         }
     }
 }
+
+impl fmt::Display for Category {
+    /// Display for the name of a scorecard box
+    ///
+    /// # Example
+    /// ```rust
+    /// use yahtzee::score::Category;
+    /// assert_eq!(format!("{}", Category::FourOfAKind), "Four of a Kind");
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Category::Aces => "Aces",
+            Category::Twos => "Twos",
+            Category::Threes => "Threes",
+            Category::Fours => "Fours",
+            Category::Fives => "Fives",
+            Category::Sixes => "Sixes",
+            Category::Sevens => "Sevens",
+            Category::Eights => "Eights",
+            Category::ThreeOfAKind => "Three of a Kind",
+            Category::FourOfAKind => "Four of a Kind",
+            Category::FullHouse => "Full House",
+            Category::SmallStraight => "Small Straight",
+            Category::LargeStraight => "Large Straight",
+            Category::Chance => "Chance",
+            Category::Yahtzee => "Yahtzee",
+        };
+        write!(f, "{}", name)
+    }
+}
@@ -0,0 +1,776 @@
+use crate::hand::{self, DiceHand, Keep, NUM_KEEPS};
+use crate::player::Player;
+use crate::probability;
+use crate::score::{Category, Score};
+use crate::storage;
+use std::path::Path;
+use std::{fs, io};
+
+/// file in the data directory the solved values are cached in
+pub const SOLVER_FILE: &str = "solver.bin";
+
+/// file in the data directory the spread of scores is cached in
+pub const SPREAD_FILE: &str = "spread.bin";
+
+const NUM_BOXES: usize = Category::STANDARD.len();
+const ALL_FILLED: u16 = (1 << NUM_BOXES) - 1;
+/// upper section totals past the bonus threshold are all the same to the
+/// rest of the game
+const UPPER_CAP: u32 = 63;
+const UPPER_BONUS: u32 = 35;
+/// points for each Yahtzee after one scored for 50
+const BONUS_YAHTZEE: u32 = 150;
+const NUM_CARDS: usize = (1 << NUM_BOXES) * (UPPER_CAP as usize + 1) * 2;
+
+/// the start of every cached table
+const CACHE_MAGIC: [u8; 4] = *b"YZTB";
+/// goes up whenever the tables are worked out differently, so caches from
+/// older versions are rebuilt
+const CACHE_VERSION: u32 = 1;
+/// the magic, version and checksum before a cached table's values
+const CACHE_HEADER_LEN: usize = 16;
+
+/// What the rest of a regular game depends on from a scorecard: which boxes
+/// are filled, the upper section total and whether a Yahtzee was scored for 50.
+///
+/// # Example
+/// ```rust
+/// use yahtzee::player::Player;
+/// use yahtzee::score::{Category, Score};
+/// use yahtzee::solver::Card;
+///
+/// let mut player = Player::new("test".to_owned());
+/// player.update_score(Score::Sixes(24));
+/// player.update_score(Score::Yahtzee(50));
+/// let card = Card::from_player(&player).unwrap();
+/// assert!(card.is_filled(Category::Sixes));
+/// assert!(!card.is_filled(Category::Chance));
+/// assert_eq!(card.upper, 24);
+/// assert!(card.yahtzee_bonus);
+/// ```
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct Card {
+    /// the filled boxes, one bit for each `Category::index`
+    pub filled: u16,
+    /// the upper section total, counted up to the bonus threshold
+    pub upper: u32,
+    /// whether another Yahtzee can be scored for 150
+    pub yahtzee_bonus: bool,
+}
+
+impl Card {
+    /// the card for a player of a regular game, or None for other rules
+    ///
+    /// Every Yahtzee scored for 150 uses up a turn without filling a box, so
+    /// it's counted as though the lowest scoring box had already been given up.
+    /// See `Solver::card` for picking that box.
+    pub fn from_player(player: &Player) -> Option<Card> {
+        if !player.rules.is_standard() {
+            return None;
+        }
+        let filled = Category::STANDARD
+            .iter()
+            .filter(|category| player.scored(**category).is_some())
+            .fold(0, |filled, category| filled | 1 << category.index());
+        Some(Card {
+            filled,
+            upper: player.upper_total().min(UPPER_CAP),
+            yahtzee_bonus: player.scored(Category::Yahtzee) == Some(Score::Yahtzee(50)),
+        })
+    }
+
+    pub fn is_filled(self, category: Category) -> bool {
+        self.filled & 1 << category.index() != 0
+    }
+
+    /// the boxes still open
+    pub fn open(self) -> impl Iterator<Item = Category> {
+        Category::STANDARD
+            .iter()
+            .copied()
+            .filter(move |category| !self.is_filled(*category))
+    }
+
+    /// the turns left in the game
+    pub fn turns_left(self) -> usize {
+        NUM_BOXES - self.filled.count_ones() as usize
+    }
+
+    /// the card after scoring points in an open box, with any upper section
+    /// bonus earned by it
+    pub fn fill(self, category: Category, points: u32) -> (Card, u32) {
+        let mut next = Card {
+            filled: self.filled | 1 << category.index(),
+            ..self
+        };
+        let mut bonus = 0;
+        if category.face().is_some() {
+            next.upper = (self.upper + points).min(UPPER_CAP);
+            if self.upper < UPPER_CAP && next.upper == UPPER_CAP {
+                bonus = UPPER_BONUS;
+            }
+        }
+        if category == Category::Yahtzee && points > 0 {
+            next.yahtzee_bonus = true;
+        }
+        (next, bonus)
+    }
+
+    fn index(self) -> usize {
+        ((self.filled as usize * (UPPER_CAP as usize + 1)) + self.upper as usize) * 2
+            + self.yahtzee_bonus as usize
+    }
+}
+
+/// The expected points still to come from every scorecard of a regular game
+/// when playing to maximise the average final score.
+///
+/// Scoring a Yahtzee for 150 uses up a turn without filling a box, which is
+/// valued as giving up whichever open box is worth least to the rest of the
+/// game. Everything else is exact.
+///
+/// # Example
+/// ```rust
+/// use yahtzee::score::Category;
+/// use yahtzee::solver::{Card, Solver};
+///
+/// // only chance is left, so hold everything worth more than 3.5 on average
+/// let solver = Solver::endgame(1);
+/// let card = Card { filled: !(1 << Category::Chance.index()) & 0x1fff, upper: 0, yahtzee_bonus: false };
+/// assert!((solver.value(card) - 23.33).abs() < 0.01);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Solver {
+    values: Vec<f32>,
+    /// the fewest filled boxes on any card that's been solved
+    min_filled: usize,
+}
+
+/// The value of every choice during one turn, from a solved scorecard.
+#[derive(Debug, Clone)]
+pub struct Turn<'a> {
+    solver: &'a Solver,
+    card: Card,
+    /// `hands[rolls][hand]` is the value of having a hand with rolls left
+    hands: [Vec<f64>; 3],
+    /// `keeps[rolls][keep]` is the value of holding dice and rolling the
+    /// rest, with rolls left after that roll
+    keeps: [Vec<f64>; 2],
+}
+
+impl Solver {
+    /// solves every scorecard, which takes a while
+    pub fn new() -> Solver {
+        Solver::endgame(NUM_BOXES)
+    }
+
+    /// solves only the scorecards with at most `turns` turns left
+    pub fn endgame(turns: usize) -> Solver {
+        let turns = turns.min(NUM_BOXES);
+        let mut solver = Solver {
+            values: vec![0.0; NUM_CARDS],
+            min_filled: NUM_BOXES - turns,
+        };
+        for card in cards(turns) {
+            let value = solver.first_roll_value(card);
+            solver.values[card.index()] = value as f32;
+        }
+        solver
+    }
+
+    /// loads the solved values cached in a file, solving and caching them if
+    /// there aren't any yet or the file isn't a cache this version wrote
+    pub fn load_or_new(path: &Path) -> io::Result<Solver> {
+        let values = load_or_cache(path, NUM_CARDS, || Solver::new().values)?;
+        Ok(Solver {
+            values,
+            min_filled: 0,
+        })
+    }
+
+    /// the expected points still to come from a scorecard
+    ///
+    /// # Panics
+    /// If the solver was built for fewer turns than are left on the card.
+    pub fn value(&self, card: Card) -> f64 {
+        assert!(
+            card.filled.count_ones() as usize >= self.min_filled,
+            "card with {} turns left has not been solved",
+            card.turns_left()
+        );
+        f64::from(self.values[card.index()])
+    }
+
+    /// the card for a player, giving up the least valuable open box for
+    /// every Yahtzee scored for 150
+    pub fn card(&self, player: &Player) -> Option<Card> {
+        let mut card = Card::from_player(player)?;
+        let bonus_yahtzees = player
+            .scores
+            .iter()
+            .filter(|score| **score == Score::Yahtzee(150))
+            .count();
+        for _ in 0..bonus_yahtzees {
+            if let Some((_, given_up)) = self.give_up(card) {
+                card = given_up;
+            }
+        }
+        Some(card)
+    }
+
+    /// the value of scoring a hand in a category, counting the points scored
+    /// now and expected later, or None if it can't be scored there
+    pub fn score_value(&self, card: Card, hand: DiceHand, category: Category) -> Option<f64> {
+        self.after_score(card, hand, category)
+            .map(|(points, next)| f64::from(points) + self.value(next))
+    }
+
+    /// the points scored by a hand in a category, counting any bonus, and
+    /// the card after, or None if it can't be scored there
+    ///
+    /// # Example
+    /// ```rust
+    /// use yahtzee::hand::DiceHand;
+    /// use yahtzee::score::Category;
+    /// use yahtzee::solver::{Card, Solver};
+    ///
+    /// let solver = Solver::endgame(1);
+    /// let card = Card { filled: 0x1fff & !(1 << Category::Sixes.index()), upper: 40, yahtzee_bonus: false };
+    /// let hand = DiceHand::from_dice(&[6, 6, 6, 6, 2]).unwrap();
+    /// let (points, next) = solver.after_score(card, hand, Category::Sixes).unwrap();
+    /// assert_eq!(points, 24 + 35);
+    /// assert_eq!(next.turns_left(), 0);
+    /// assert_eq!(solver.after_score(next, hand, Category::Sixes), None);
+    /// ```
+    pub fn after_score(
+        &self,
+        card: Card,
+        hand: DiceHand,
+        category: Category,
+    ) -> Option<(u32, Card)> {
+        if card.is_filled(category) {
+            let is_yahtzee = hand.score(Category::Yahtzee) > 0;
+            if category == Category::Yahtzee && card.yahtzee_bonus && is_yahtzee {
+                let next = self.give_up(card).map_or(card, |(_, next)| next);
+                return Some((BONUS_YAHTZEE, next));
+            }
+            return None;
+        }
+
+        let points = hand.score(category);
+        let (next, bonus) = card.fill(category, points);
+        Some((points + bonus, next))
+    }
+
+    /// the best category to score a hand in and its value
+    pub fn best_score(&self, card: Card, hand: DiceHand) -> Option<(Category, f64)> {
+        Category::STANDARD
+            .iter()
+            .filter_map(|category| {
+                self.score_value(card, hand, *category)
+                    .map(|value| (*category, value))
+            })
+            .fold(None, |best, (category, value)| match best {
+                Some((_, best_value)) if best_value >= value => best,
+                _ => Some((category, value)),
+            })
+    }
+
+    /// works out the value of every hold and score for a turn on a card
+    pub fn turn(&self, card: Card) -> Turn<'_> {
+        // most boxes lead to the same card whatever is scored in them, so the
+        // value of the card after is only looked up once for each score
+        let open: Vec<Category> = card.open().collect();
+        let mut later: Vec<[Option<f64>; 51]> = vec![[None; 51]; open.len()];
+        let mut bonus_yahtzee = None;
+        let scored: Vec<f64> = DiceHand::all()
+            .map(|hand| {
+                let scores = hand.scores();
+                let mut best = f64::MIN;
+                for (category, later) in open.iter().zip(later.iter_mut()) {
+                    let points = scores[category.index()];
+                    let value = *later[points as usize].get_or_insert_with(|| {
+                        let (next, bonus) = card.fill(*category, points);
+                        f64::from(bonus) + self.value(next)
+                    });
+                    if f64::from(points) + value > best {
+                        best = f64::from(points) + value;
+                    }
+                }
+                if card.yahtzee_bonus && scores[Category::Yahtzee.index()] > 0 {
+                    let value = *bonus_yahtzee.get_or_insert_with(|| {
+                        self.score_value(card, hand, Category::Yahtzee)
+                            .expect("a second yahtzee can be scored")
+                    });
+                    best = best.max(value);
+                }
+                best
+            })
+            .collect();
+        let keeps_0 = keep_values(&scored);
+        let hands_1 = hand_values(&keeps_0);
+        let keeps_1 = keep_values(&hands_1);
+        let hands_2 = hand_values(&keeps_1);
+        Turn {
+            solver: self,
+            card,
+            hands: [scored, hands_1, hands_2],
+            keeps: [keeps_0, keeps_1],
+        }
+    }
+
+    /// the box that's best to give up without scoring, and the value of the
+    /// card after
+    fn give_up(&self, card: Card) -> Option<(f64, Card)> {
+        card.open()
+            .map(|category| {
+                let (next, _) = card.fill(category, 0);
+                (self.value(next), next)
+            })
+            .fold(None, |best, (value, next)| match best {
+                Some((best_value, _)) if best_value >= value => best,
+                _ => Some((value, next)),
+            })
+    }
+
+    fn first_roll_value(&self, card: Card) -> f64 {
+        let turn = self.turn(card);
+        hand::first_roll()
+            .iter()
+            .map(|(hand, probability)| probability * turn.hands[2][hand.index()])
+            .sum()
+    }
+}
+
+impl Default for Solver {
+    fn default() -> Solver {
+        Solver::new()
+    }
+}
+
+impl Turn<'_> {
+    pub fn card(&self) -> Card {
+        self.card
+    }
+
+    /// the value of having a hand with rolls left, playing on as well as possible
+    pub fn hand_value(&self, hand: DiceHand, rolls_left: u32) -> f64 {
+        self.hands[rolls_left.min(2) as usize][hand.index()]
+    }
+
+    /// the value of holding dice and rolling the rest, when there are
+    /// `rolls_left` rolls before that roll
+    ///
+    /// # Panics
+    /// If there are no rolls left.
+    pub fn keep_value(&self, keep: Keep, rolls_left: u32) -> f64 {
+        assert!(rolls_left > 0, "no rolls left to hold dice for");
+        self.keeps[rolls_left.min(2) as usize - 1][keep.index()]
+    }
+
+    /// the best dice to hold from a hand with rolls left and the value of
+    /// holding them
+    pub fn best_keep(&self, hand: DiceHand, rolls_left: u32) -> (Keep, f64) {
+        hand.keeps()
+            .iter()
+            .map(|keep| (*keep, self.keep_value(*keep, rolls_left)))
+            .fold(
+                None,
+                |best: Option<(Keep, f64)>, (keep, value)| match best {
+                    Some((_, best_value)) if best_value >= value => best,
+                    _ => Some((keep, value)),
+                },
+            )
+            .expect("every hand can be held")
+    }
+
+    /// the value of scoring a hand in a category
+    pub fn score_value(&self, hand: DiceHand, category: Category) -> Option<f64> {
+        self.solver.score_value(self.card, hand, category)
+    }
+
+    pub fn best_score(&self, hand: DiceHand) -> Option<(Category, f64)> {
+        self.solver.best_score(self.card, hand)
+    }
+}
+
+/// How the points still to come from a scorecard spread around the solver's
+/// expected value, when playing to maximise the average.
+///
+/// # Example
+/// ```rust
+/// use yahtzee::score::Category;
+/// use yahtzee::solver::{Card, Solver, Spread};
+///
+/// let solver = Solver::endgame(1);
+/// let spread = Spread::new(&solver);
+/// let chance = Card { filled: !(1 << Category::Chance.index()) & 0x1fff, upper: 0, yahtzee_bonus: false };
+/// let outlook = spread.outlook(&solver, chance);
+/// assert!((outlook.mean - 23.33).abs() < 0.01);
+/// assert!(outlook.variance > 1.0 && outlook.variance < 25.0);
+/// assert_eq!(outlook.bonus_yahtzees, 0.0);
+/// assert!(outlook.chance_at_least(5.0) > 0.999999);
+/// assert!(outlook.chance_at_least(31.0) < 0.05);
+/// assert!(outlook.chance_at_least(45.0) < 1e-6);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Spread {
+    /// the variance of the points other than bonus Yahtzees, then the
+    /// expected number of bonus Yahtzees, for each card
+    values: Vec<f32>,
+    min_filled: usize,
+}
+
+/// The points still to come from a scorecard, modelled as a normally
+/// distributed amount plus 150 for each Yahtzee scored for 150, which come
+/// along as a Poisson process. The Yahtzees give scores a long tail that a
+/// normal distribution alone badly misses.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Outlook {
+    /// the mean of the points other than from bonus Yahtzees
+    pub mean: f64,
+    /// the variance of the points other than from bonus Yahtzees
+    pub variance: f64,
+    /// the expected number of Yahtzees scored for 150
+    pub bonus_yahtzees: f64,
+}
+
+impl Spread {
+    /// works out the spread for every scorecard the solver has solved,
+    /// which takes a while for a full solver
+    pub fn new(solver: &Solver) -> Spread {
+        let mut spread = Spread {
+            values: vec![0.0; NUM_CARDS * 2],
+            min_filled: solver.min_filled,
+        };
+
+        for card in cards(NUM_BOXES - solver.min_filled) {
+            // the same choices as the solver makes, tracking the expected
+            // number of bonus Yahtzees and the expected square of the other
+            // points alongside the expected value
+            let turn = solver.turn(card);
+            let open: Vec<Category> = card.open().collect();
+            // for each score in each open box, looked up once
+            let mut later: Vec<[Option<Later>; 51]> = vec![[None; 51]; open.len()];
+            let mut bonus_yahtzee = None;
+            let after = |points: u32, next: Card, bonus: bool| {
+                let outlook = spread.outlook(solver, next);
+                Later {
+                    points: f64::from(points),
+                    value: solver.value(next),
+                    bonus,
+                    outlook,
+                }
+            };
+            let (yahtzees, squares): (Vec<f64>, Vec<f64>) = DiceHand::all()
+                .map(|hand| {
+                    let scores = hand.scores();
+                    let mut best: Option<Later> = None;
+                    for (category, later) in open.iter().zip(later.iter_mut()) {
+                        let points = scores[category.index()];
+                        let later = *later[points as usize].get_or_insert_with(|| {
+                            let (next, bonus) = card.fill(*category, points);
+                            after(points + bonus, next, false)
+                        });
+                        if best.is_none_or(|best| later.total() > best.total()) {
+                            best = Some(later);
+                        }
+                    }
+                    if card.yahtzee_bonus && scores[Category::Yahtzee.index()] > 0 {
+                        let later = *bonus_yahtzee.get_or_insert_with(|| {
+                            let (points, next) = solver
+                                .after_score(card, hand, Category::Yahtzee)
+                                .expect("a second yahtzee can be scored");
+                            after(points, next, true)
+                        });
+                        if best.is_none_or(|best| later.total() > best.total()) {
+                            best = Some(later);
+                        }
+                    }
+                    best.expect("the card has open boxes").moments()
+                })
+                .unzip();
+
+            let mut moments = [yahtzees, squares].map(|scored| {
+                let keeps_0 = keep_values(&scored);
+                let hands_1 = best_keep_values(&turn, &keeps_0, 1);
+                let keeps_1 = keep_values(&hands_1);
+                best_keep_values(&turn, &keeps_1, 2)
+            });
+            let [yahtzees, squares] = moments.each_mut().map(|hands| {
+                hand::first_roll()
+                    .iter()
+                    .map(|(hand, probability)| probability * hands[hand.index()])
+                    .sum::<f64>()
+            });
+            let mean = solver.value(card) - f64::from(BONUS_YAHTZEE) * yahtzees;
+            spread.values[card.index() * 2] = (squares - mean * mean).max(0.0) as f32;
+            spread.values[card.index() * 2 + 1] = yahtzees as f32;
+        }
+        spread
+    }
+
+    /// loads the spread cached in a file, working it out and caching it if
+    /// there isn't one yet
+    pub fn load_or_new(path: &Path, solver: &Solver) -> io::Result<Spread> {
+        let values = load_or_cache(path, NUM_CARDS * 2, || Spread::new(solver).values)?;
+        Ok(Spread {
+            values,
+            min_filled: solver.min_filled,
+        })
+    }
+
+    /// the points still to come from a scorecard
+    ///
+    /// # Panics
+    /// If the spread was worked out for fewer turns than are left on the card.
+    pub fn outlook(&self, solver: &Solver, card: Card) -> Outlook {
+        assert!(
+            card.filled.count_ones() as usize >= self.min_filled,
+            "card with {} turns left has not been solved",
+            card.turns_left()
+        );
+        let bonus_yahtzees = f64::from(self.values[card.index() * 2 + 1]);
+        Outlook {
+            mean: solver.value(card) - f64::from(BONUS_YAHTZEE) * bonus_yahtzees,
+            variance: f64::from(self.values[card.index() * 2]),
+            bonus_yahtzees,
+        }
+    }
+}
+
+impl Outlook {
+    /// the expected points still to come
+    pub fn expected(&self) -> f64 {
+        self.mean + f64::from(BONUS_YAHTZEE) * self.bonus_yahtzees
+    }
+
+    /// the chance of scoring at least some number of points
+    pub fn chance_at_least(&self, points: f64) -> f64 {
+        poisson(self.bonus_yahtzees)
+            .map(|(yahtzees, probability)| {
+                let total = self.mean + f64::from(BONUS_YAHTZEE) * yahtzees;
+                // the points are whole numbers, so half a point short counts
+                probability * probability::chance_above(total - points + 0.5, self.variance)
+            })
+            .sum()
+    }
+
+    /// the chance of finishing ahead of another player, starting `lead`
+    /// points ahead of them, with ties counting as half
+    pub fn chance_ahead(&self, lead: f64, other: &Outlook) -> f64 {
+        let variance = self.variance + other.variance;
+        poisson(self.bonus_yahtzees)
+            .flat_map(|(mine, my_probability)| {
+                poisson(other.bonus_yahtzees).map(move |(theirs, probability)| {
+                    let yahtzees = f64::from(BONUS_YAHTZEE) * (mine - theirs);
+                    let ahead = lead + self.mean - other.mean + yahtzees;
+                    my_probability * probability * probability::chance_above(ahead, variance)
+                })
+            })
+            .sum()
+    }
+}
+
+/// What scoring a hand in a box leads to, for working out the spread
+#[derive(Debug, Clone, Copy)]
+struct Later {
+    points: f64,
+    /// the expected points still to come after
+    value: f64,
+    /// whether the points are for a bonus Yahtzee
+    bonus: bool,
+    outlook: Outlook,
+}
+
+impl Later {
+    fn total(&self) -> f64 {
+        self.points + self.value
+    }
+
+    /// the expected number of bonus Yahtzees and expected square of the
+    /// other points, from scoring now on
+    fn moments(&self) -> (f64, f64) {
+        let (yahtzees, points) = match self.bonus {
+            true => (1.0, 0.0),
+            false => (0.0, self.points),
+        };
+        let mean = self.outlook.mean;
+        (
+            yahtzees + self.outlook.bonus_yahtzees,
+            points * points + 2.0 * points * mean + self.outlook.variance + mean * mean,
+        )
+    }
+}
+
+/// the chance of each number of events happening when `mean` are expected,
+/// until the rest are too unlikely to matter
+fn poisson(mean: f64) -> impl Iterator<Item = (f64, f64)> {
+    let mut left = 1.0;
+    (0..=NUM_BOXES as u32)
+        .scan((-mean).exp(), move |probability, count| {
+            let term = *probability;
+            *probability *= mean / f64::from(count + 1);
+            Some((f64::from(count), term))
+        })
+        .take_while(move |(_, probability)| {
+            let going = left > 1e-12;
+            left -= probability;
+            going
+        })
+}
+
+/// Everything the computer players that play optimally are built from
+#[derive(Debug, Clone)]
+pub struct Solved {
+    pub solver: Solver,
+    pub spread: Spread,
+}
+
+impl Solved {
+    /// the points still to come from a scorecard
+    pub fn outlook(&self, card: Card) -> Outlook {
+        self.spread.outlook(&self.solver, card)
+    }
+}
+
+/// for each hand, a value of the keep the solver holds from it
+fn best_keep_values(turn: &Turn, keeps: &[f64], rolls_left: u32) -> Vec<f64> {
+    DiceHand::all()
+        .map(|hand| keeps[turn.best_keep(hand, rolls_left).0.index()])
+        .collect()
+}
+
+/// Reads a table of values for every card cached in a file, working it out
+/// and caching it if there isn't one yet or the file isn't a table this
+/// version wrote.
+fn load_or_cache<F>(path: &Path, len: usize, new: F) -> io::Result<Vec<f32>>
+where
+    F: FnOnce() -> Vec<f32>,
+{
+    match fs::read(path) {
+        Ok(bytes) => {
+            if let Some(values) = read_cache(&bytes, len) {
+                return Ok(values);
+            }
+        }
+        Err(error) if error.kind() != io::ErrorKind::NotFound => return Err(error),
+        Err(_) => {}
+    }
+
+    let values = new();
+    write_cache(path, &values)?;
+    Ok(values)
+}
+
+/// caches a table of values in a file, after a header with the magic, the
+/// version and a checksum of the values
+fn write_cache(path: &Path, values: &[f32]) -> io::Result<()> {
+    let mut bytes = Vec::with_capacity(CACHE_HEADER_LEN + values.len() * 4);
+    bytes.extend_from_slice(&CACHE_MAGIC);
+    bytes.extend_from_slice(&CACHE_VERSION.to_le_bytes());
+    bytes.extend_from_slice(&[0; 8]);
+    bytes.extend(values.iter().flat_map(|value| value.to_le_bytes()));
+    let sum = checksum(&bytes[CACHE_HEADER_LEN..]);
+    bytes[8..CACHE_HEADER_LEN].copy_from_slice(&sum.to_le_bytes());
+    storage::save_bytes(path, &bytes)
+}
+
+/// the `len` values in a cached table, if it has this version's header and
+/// its checksum matches
+fn read_cache(bytes: &[u8], len: usize) -> Option<Vec<f32>> {
+    if bytes.len() != CACHE_HEADER_LEN + len * 4 {
+        return None;
+    }
+    let (header, values) = bytes.split_at(CACHE_HEADER_LEN);
+    if header[..4] != CACHE_MAGIC
+        || header[4..8] != CACHE_VERSION.to_le_bytes()
+        || header[8..] != checksum(values).to_le_bytes()
+    {
+        return None;
+    }
+    Some(
+        values
+            .chunks_exact(4)
+            .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+            .collect(),
+    )
+}
+
+/// 64 bit FNV-1a hash, enough to catch a cache that was cut short or damaged
+fn checksum(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// every card that can come up with at most `turns` turns left, the ones
+/// with the fewest turns left first
+fn cards(turns: usize) -> impl Iterator<Item = Card> {
+    let reachable = reachable_upper_totals();
+    (NUM_BOXES - turns..NUM_BOXES)
+        .rev()
+        .flat_map(|num_filled| {
+            (0..ALL_FILLED).filter(move |filled| filled.count_ones() as usize == num_filled)
+        })
+        .flat_map(move |filled| {
+            let upper_totals = reachable[filled as usize & 0b111111];
+            (0..=UPPER_CAP)
+                .filter(move |upper| upper_totals & 1 << upper != 0)
+                .flat_map(move |upper| {
+                    [false, true].iter().map(move |yahtzee_bonus| Card {
+                        filled,
+                        upper,
+                        yahtzee_bonus: *yahtzee_bonus,
+                    })
+                })
+        })
+        .filter(|card| !card.yahtzee_bonus || card.is_filled(Category::Yahtzee))
+}
+
+/// the value of each keep given the value of each hand that can be rolled
+pub(crate) fn keep_values(hands: &[f64]) -> Vec<f64> {
+    (0..NUM_KEEPS)
+        .map(|index| {
+            Keep::from_index(index)
+                .outcomes()
+                .iter()
+                .map(|(hand, probability)| probability * hands[hand.index()])
+                .sum()
+        })
+        .collect()
+}
+
+/// the value of each hand from holding its best dice
+pub(crate) fn hand_values(keeps: &[f64]) -> Vec<f64> {
+    DiceHand::all()
+        .map(|hand| {
+            hand.keeps()
+                .iter()
+                .map(|keep| keeps[keep.index()])
+                .fold(
+                    f64::MIN,
+                    |best, value| if value > best { value } else { best },
+                )
+        })
+        .collect()
+}
+
+/// for each set of filled upper boxes, a bit set of the upper totals up to
+/// the bonus threshold that can be reached with them
+fn reachable_upper_totals() -> [u64; 64] {
+    let mut reachable = [0u64; 64];
+    reachable[0] = 1;
+    for boxes in 1..64usize {
+        let face = boxes.trailing_zeros();
+        let before = reachable[boxes & (boxes - 1)];
+        let mut totals = 0;
+        for count in 0..=5 {
+            for upper in (0..=UPPER_CAP).filter(|upper| before & 1 << upper != 0) {
+                totals |= 1 << (upper + count * (face + 1)).min(UPPER_CAP);
+            }
+        }
+        reachable[boxes] = totals;
+    }
+    reachable
+}
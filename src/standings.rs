@@ -0,0 +1,217 @@
+use crate::player::Player;
+use serde::{Deserialize, Serialize};
+use std::{fmt, str};
+
+/// How players finishing on the same score are separated
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub enum TieBreak {
+    /// players on the same score share their place
+    #[default]
+    Shared,
+    /// the player who scored more Yahtzees places higher
+    MostYahtzees,
+    /// the player with the higher upper section total places higher
+    UpperSection,
+}
+
+impl TieBreak {
+    fn key(self, player: &Player) -> u32 {
+        match self {
+            TieBreak::Shared => 0,
+            TieBreak::MostYahtzees => player.yahtzees(),
+            TieBreak::UpperSection => player.upper_total(),
+        }
+    }
+}
+
+impl fmt::Display for TieBreak {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TieBreak::Shared => write!(f, "a shared place"),
+            TieBreak::MostYahtzees => write!(f, "most Yahtzees"),
+            TieBreak::UpperSection => write!(f, "a higher upper section"),
+        }
+    }
+}
+
+/// The text given wasn't the name of a tie-break
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ParseTieBreakError(pub String);
+
+impl fmt::Display for ParseTieBreakError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unknown tie-break \"{}\", use shared, yahtzees or upper",
+            self.0
+        )
+    }
+}
+
+impl str::FromStr for TieBreak {
+    type Err = ParseTieBreakError;
+
+    fn from_str(input: &str) -> Result<TieBreak, ParseTieBreakError> {
+        match input.trim().to_lowercase().as_str() {
+            "shared" => Ok(TieBreak::Shared),
+            "yahtzees" => Ok(TieBreak::MostYahtzees),
+            "upper" => Ok(TieBreak::UpperSection),
+            _ => Err(ParseTieBreakError(input.to_owned())),
+        }
+    }
+}
+
+/// Where one player finished
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Standing {
+    /// finishing place starting from 1, shared by players who couldn't be separated
+    pub place: usize,
+    pub name: String,
+    pub score: u32,
+    /// points behind the player in first place
+    pub behind: u32,
+}
+
+/// Ranks finished players by score, separating players on the same score
+/// with the tie-break.
+///
+/// # Example
+/// ```rust
+/// use yahtzee::player::Player;
+/// use yahtzee::score::Score;
+/// use yahtzee::standings::{self, TieBreak};
+///
+/// let mut ann = Player::new("Ann".to_owned());
+/// ann.update_score(Score::Yahtzee(50));
+/// let mut bob = Player::new("Bob".to_owned());
+/// bob.update_score(Score::Chance(20));
+/// bob.update_score(Score::Sixes(30));
+/// let mut cat = Player::new("Cat".to_owned());
+/// cat.update_score(Score::Chance(30));
+/// let players = [bob, cat, ann];
+///
+/// let shared = standings::standings(&players, TieBreak::Shared);
+/// let places: Vec<(usize, &str)> = shared.iter().map(|s| (s.place, s.name.as_str())).collect();
+/// assert_eq!(places, vec![(1, "Bob"), (1, "Ann"), (3, "Cat")]);
+/// assert_eq!(shared[2].behind, 20);
+/// assert_eq!(standings::margin(&shared), None);
+///
+/// let broken = standings::standings(&players, TieBreak::MostYahtzees);
+/// assert_eq!((broken[0].place, broken[0].name.as_str()), (1, "Ann"));
+/// assert_eq!((broken[1].place, broken[1].name.as_str()), (2, "Bob"));
+/// assert_eq!(standings::margin(&broken), Some(0));
+/// ```
+pub fn standings(players: &[Player], tie_break: TieBreak) -> Vec<Standing> {
+    let entries: Vec<(u32, u32)> = players
+        .iter()
+        .map(|player| (player.score, tie_break.key(player)))
+        .collect();
+    to_standings(&entries, |i| players[i].name.clone())
+}
+
+/// Each player's finishing place, in the order the players are given.
+///
+/// # Example
+/// ```rust
+/// use yahtzee::player::Player;
+/// use yahtzee::score::Score;
+/// use yahtzee::standings::{self, TieBreak};
+///
+/// let mut ann = Player::new("Ann".to_owned());
+/// ann.update_score(Score::Chance(20));
+/// let mut bob = Player::new("Bob".to_owned());
+/// bob.update_score(Score::Chance(30));
+/// let cat = Player::new("Cat".to_owned());
+/// assert_eq!(standings::places(&[ann, bob, cat], TieBreak::Shared), vec![2, 1, 3]);
+/// ```
+pub fn places(players: &[Player], tie_break: TieBreak) -> Vec<usize> {
+    let entries: Vec<(u32, u32)> = players
+        .iter()
+        .map(|player| (player.score, tie_break.key(player)))
+        .collect();
+    let mut places = vec![0; players.len()];
+    for (i, place) in rank(&entries) {
+        places[i] = place;
+    }
+    places
+}
+
+/// Ranks teams by the total of their players' scores, separating teams on
+/// the same total with the tie-break summed over their players.
+///
+/// # Example
+/// ```rust
+/// use yahtzee::player::Player;
+/// use yahtzee::score::Score;
+/// use yahtzee::standings::{self, TieBreak};
+///
+/// let mut ann = Player::new("Ann".to_owned());
+/// ann.update_score(Score::Yahtzee(50));
+/// let mut bob = Player::new("Bob".to_owned());
+/// bob.update_score(Score::Chance(20));
+/// let mut cat = Player::new("Cat".to_owned());
+/// cat.update_score(Score::Chance(30));
+/// let dan = Player::new("Dan".to_owned());
+///
+/// let teams = vec![
+///     ("Red".to_owned(), vec![&cat, &dan]),
+///     ("Blue".to_owned(), vec![&ann, &bob]),
+/// ];
+/// let ranked = standings::team_standings(&teams, TieBreak::Shared);
+/// assert_eq!((ranked[0].name.as_str(), ranked[0].score), ("Blue", 70));
+/// assert_eq!(ranked[1].behind, 40);
+/// ```
+pub fn team_standings(teams: &[(String, Vec<&Player>)], tie_break: TieBreak) -> Vec<Standing> {
+    let entries: Vec<(u32, u32)> = teams
+        .iter()
+        .map(|(_, players)| {
+            let score = players.iter().map(|player| player.score).sum();
+            let key = players.iter().map(|player| tie_break.key(player)).sum();
+            (score, key)
+        })
+        .collect();
+    to_standings(&entries, |i| teams[i].0.clone())
+}
+
+/// Standings for scores and tie-break keys, named by their index.
+pub(crate) fn to_standings<F: Fn(usize) -> String>(
+    entries: &[(u32, u32)],
+    name: F,
+) -> Vec<Standing> {
+    let top = entries.iter().map(|(score, _)| *score).max().unwrap_or(0);
+    rank(entries)
+        .into_iter()
+        .map(|(i, place)| Standing {
+            place,
+            name: name(i),
+            score: entries[i].0,
+            behind: top - entries[i].0,
+        })
+        .collect()
+}
+
+/// the index and place of each score and tie-break key, best first
+fn rank(entries: &[(u32, u32)]) -> Vec<(usize, usize)> {
+    let mut ranked: Vec<usize> = (0..entries.len()).collect();
+    // a stable sort keeps entries that can't be separated in turn order
+    ranked.sort_by_key(|i| std::cmp::Reverse(entries[*i]));
+
+    let mut places: Vec<(usize, usize)> = Vec::with_capacity(ranked.len());
+    for (position, i) in ranked.iter().enumerate() {
+        let place = match position.checked_sub(1) {
+            Some(previous) if entries[ranked[previous]] == entries[*i] => places[previous].1,
+            _ => position + 1,
+        };
+        places.push((*i, place));
+    }
+    places
+}
+
+/// the points the winner finished ahead of second place by, or None when
+/// first place is shared or nobody else played
+pub fn margin(standings: &[Standing]) -> Option<u32> {
+    match standings {
+        [first, second, ..] if second.place > first.place => Some(first.score - second.score),
+        _ => None,
+    }
+}
@@ -0,0 +1,137 @@
+use crate::player::Player;
+use crate::score::{Category, Score};
+use crate::storage;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io;
+use std::path::Path;
+
+/// file in the data directory that holds everyone's statistics
+pub const STATS_FILE: &str = "stats.json";
+
+/// Running totals for one person across every game they've finished.
+///
+/// # Example
+/// ```rust
+/// use yahtzee::player::Player;
+/// use yahtzee::score::{Category, Score};
+/// use yahtzee::stats::PlayerStats;
+///
+/// let mut player = Player::new("test".to_owned());
+/// player.update_score(Score::Yahtzee(50));
+/// player.update_score(Score::Chance(0));
+///
+/// let mut stats = PlayerStats::default();
+/// stats.record(&player);
+/// stats.record(&player);
+/// assert_eq!(stats.games_played, 2);
+/// assert_eq!(stats.average_score(), 50.0);
+/// assert_eq!(stats.yahtzees, 2);
+/// assert_eq!(stats.most_zeroed(), vec![(Category::Chance, 2)]);
+/// ```
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+pub struct PlayerStats {
+    pub games_played: u32,
+    pub total_score: u64,
+    pub high_score: u32,
+    pub upper_bonuses: u32,
+    pub yahtzees: u32,
+    pub zeroed: BTreeMap<Category, u32>,
+}
+
+impl PlayerStats {
+    /// add a finished game to the totals
+    pub fn record(&mut self, player: &Player) {
+        self.games_played += 1;
+        self.total_score += u64::from(player.score);
+        self.high_score = self.high_score.max(player.score);
+
+        for score in player.scores.iter() {
+            match score {
+                Score::UpperScoreBonus(_) => self.upper_bonuses += 1,
+                Score::Yahtzee(points) if *points > 0 => self.yahtzees += 1,
+                _ => (),
+            }
+            if let (Some(category), 0) = (score.category(), score.value()) {
+                *self.zeroed.entry(category).or_insert(0) += 1;
+            }
+        }
+    }
+
+    pub fn average_score(&self) -> f64 {
+        if self.games_played == 0 {
+            return 0.0;
+        }
+        self.total_score as f64 / f64::from(self.games_played)
+    }
+
+    /// fraction of games where the upper section bonus was earned
+    pub fn bonus_rate(&self) -> f64 {
+        if self.games_played == 0 {
+            return 0.0;
+        }
+        f64::from(self.upper_bonuses) / f64::from(self.games_played)
+    }
+
+    /// the categories most often scored as zero, most frequent first
+    pub fn most_zeroed(&self) -> Vec<(Category, u32)> {
+        let mut zeroed: Vec<(Category, u32)> = self
+            .zeroed
+            .iter()
+            .map(|(category, count)| (*category, *count))
+            .collect();
+        zeroed.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        zeroed
+    }
+}
+
+/// Everyone's statistics, keyed by player name and stored as JSON.
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct StatsStore {
+    pub players: BTreeMap<String, PlayerStats>,
+}
+
+impl StatsStore {
+    /// loads the store from a file, starting empty if there isn't one yet
+    pub fn load(path: &Path) -> io::Result<StatsStore> {
+        storage::load_json(path)
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        storage::save_json(path, self)
+    }
+
+    /// add each player's finished game to their statistics
+    ///
+    /// Only games played with five six-sided dice are recorded so averages
+    /// stay comparable.
+    pub fn record_game(&mut self, players: &[Player]) {
+        for player in players.iter().filter(|player| player.rules.is_standard()) {
+            self.players
+                .entry(player.name.clone())
+                .or_default()
+                .record(player);
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&PlayerStats> {
+        self.players.get(name)
+    }
+}
+
+/// Records a finished game in the statistics file in the data directory.
+pub fn record_game(players: &[Player]) -> io::Result<()> {
+    if !players.iter().any(|player| player.rules.is_standard()) {
+        return Ok(());
+    }
+
+    let path = storage::data_file(STATS_FILE)?;
+    let mut store = StatsStore::load(&path)?;
+    store.record_game(players);
+    store.save(&path)
+}
+
+/// Reads the statistics file in the data directory.
+pub fn load() -> io::Result<StatsStore> {
+    StatsStore::load(&storage::data_file(STATS_FILE)?)
+}
@@ -0,0 +1,51 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::{env, fs, io};
+
+/// environment variable that overrides where saved data is kept
+pub const DATA_DIR_VAR: &str = "YAHTZEE_DATA_DIR";
+
+/// The directory yahtzee keeps its files in: `$YAHTZEE_DATA_DIR` if set,
+/// otherwise a `yahtzee` folder in the user's data directory.
+pub fn data_dir() -> Option<PathBuf> {
+    match env::var_os(DATA_DIR_VAR) {
+        Some(dir) => Some(PathBuf::from(dir)),
+        None => dirs::data_dir().map(|dir| dir.join("yahtzee")),
+    }
+}
+
+/// the path of a file in the data directory
+pub fn data_file(name: &str) -> io::Result<PathBuf> {
+    data_dir()
+        .map(|dir| dir.join(name))
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no data directory found"))
+}
+
+/// Reads a value stored as JSON, or the default value if the file doesn't exist yet.
+pub fn load_json<T: DeserializeOwned + Default>(path: &Path) -> io::Result<T> {
+    match fs::read_to_string(path) {
+        Ok(contents) => serde_json::from_str(&contents).map_err(io::Error::from),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(T::default()),
+        Err(error) => Err(error),
+    }
+}
+
+/// Writes a value as JSON, creating the parent directory if needed.
+///
+/// The value is written to a temporary file first and moved into place so an
+/// interrupted save never leaves a half written file behind.
+pub fn save_json<T: Serialize>(path: &Path, value: &T) -> io::Result<()> {
+    let contents = serde_json::to_string_pretty(value).map_err(io::Error::from)?;
+    save_bytes(path, contents.as_bytes())
+}
+
+/// Writes a file the same way as `save_json`, for data that isn't JSON.
+pub fn save_bytes(path: &Path, contents: &[u8]) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let temporary = path.with_extension("tmp");
+    fs::write(&temporary, contents)?;
+    fs::rename(&temporary, path)
+}
@@ -0,0 +1,599 @@
+use crate::daily;
+use crate::endgame::{EndgamePoints, Showdown, EXACT_TURNS};
+use crate::engine::{Engine, EngineError, Fault, MOVE_TIMEOUT};
+use crate::game::ROLLS_PER_TURN;
+use crate::hand::{DiceHand, Keep};
+use crate::player::Player;
+use crate::rules::Rules;
+use crate::score::{Category, Score};
+use crate::solver::{self, Card, Outlook, Solved, Solver, Turn};
+use crate::threshold::{Chances, Threshold};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::{fmt, str};
+
+/// share of the expected final score added to the chance of winning, so
+/// choices that win equally often are settled by points
+pub(crate) const TIE_BREAK: f64 = 1e-12;
+
+/// Decision making for a computer controlled player.
+pub trait Strategy {
+    /// the dice (numbered from 1) to reroll, with nothing meaning stop and score
+    fn reroll(&mut self, player: &Player, rolls_left: u32) -> Vec<u8>;
+
+    /// one of the passed in possible scores to record for the player
+    fn score(&mut self, player: &Player, possible_scores: &[Score]) -> Score;
+
+    /// called with every player in the game before each of the strategy's
+    /// turns, for strategies that play differently depending on the others
+    fn observe(&mut self, _players: &[Player], _current: usize) {}
+
+    /// anything done wrong by strategies played by other programs, which are
+    /// made up for with safe moves
+    fn faults(&self) -> &[Fault] {
+        &[]
+    }
+}
+
+/// Chases the most common face and takes whichever score is worth the most.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Greedy;
+
+impl Strategy for Greedy {
+    fn reroll(&mut self, player: &Player, _rolls_left: u32) -> Vec<u8> {
+        let mut best_face = 0;
+        let mut best_count = 0;
+        for die_face in 1..=player.rules.num_faces {
+            let count = player.dice.iter().filter(|die| **die == die_face).count();
+            if count >= best_count {
+                best_face = die_face;
+                best_count = count;
+            }
+        }
+
+        (1..=player.dice.len() as u8)
+            .filter(|die| player.dice[*die as usize - 1] != best_face)
+            .collect()
+    }
+
+    fn score(&mut self, _player: &Player, possible_scores: &[Score]) -> Score {
+        *possible_scores
+            .iter()
+            .rev()
+            .max_by_key(|score| score.value())
+            .expect("no scores left to choose from")
+    }
+}
+
+/// Plays to maximise the average final score, as worked out by the solver.
+///
+/// # Example
+/// ```rust
+/// use yahtzee::player::Player;
+/// use yahtzee::score::{Category, Score};
+/// use yahtzee::solver::Solver;
+/// use yahtzee::strategy::{Expected, Strategy};
+///
+/// let solver = Solver::endgame(1);
+/// let mut player = Player::new("bot".to_owned());
+/// for category in Category::STANDARD.iter().filter(|category| **category != Category::Chance) {
+///     player.update_score(category.score(0));
+/// }
+/// player.dice = vec![6, 6, 1, 2, 5];
+/// assert_eq!(Expected::new(&solver).reroll(&player, 2), vec![3, 4]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Expected<'a> {
+    solver: &'a Solver,
+    /// the last turn worked out, which lasts until the card changes
+    turn: Option<Turn<'a>>,
+}
+
+impl<'a> Expected<'a> {
+    pub fn new(solver: &'a Solver) -> Expected<'a> {
+        Expected { solver, turn: None }
+    }
+
+    fn turn(&mut self, player: &Player) -> &Turn<'a> {
+        let card = solver_card(self.solver, player);
+        if self.turn.as_ref().map(Turn::card) != Some(card) {
+            self.turn = Some(self.solver.turn(card));
+        }
+        self.turn.as_ref().expect("the turn was just worked out")
+    }
+}
+
+impl Strategy for Expected<'_> {
+    fn reroll(&mut self, player: &Player, rolls_left: u32) -> Vec<u8> {
+        let hand = player_hand(player);
+        let (keep, _) = self.turn(player).best_keep(hand, rolls_left);
+        reroll_for(&player.dice, &keep.dice())
+    }
+
+    fn score(&mut self, player: &Player, possible_scores: &[Score]) -> Score {
+        let hand = player_hand(player);
+        let turn = self.turn(player);
+        best_by(possible_scores, |category| turn.score_value(hand, category))
+    }
+}
+
+/// Plays to beat the other players rather than for points: every choice is
+/// the one most likely to finish ahead of whichever opponent is expected to
+/// finish highest.
+///
+/// Choices in the current turn are exact. Once the opponent is down to their
+/// last few turns, the chance of each of their final scores is worked out
+/// exactly, assuming they play for points, and so is the rest of the game.
+/// Before that, the rest of each player's game is estimated from the spread
+/// of the points still to come for their scorecard. Either way the strategy
+/// gambles when behind and plays safe when ahead. Without opponents it plays
+/// for points.
+///
+/// # Example
+/// ```rust
+/// use yahtzee::player::Player;
+/// use yahtzee::score::{Category, Score};
+/// use yahtzee::solver::{Solved, Solver, Spread};
+/// use yahtzee::strategy::{Strategy, WinProbability};
+///
+/// let solver = Solver::endgame(1);
+/// let spread = Spread::new(&solver);
+/// let solved = Solved { solver, spread };
+/// let mut player = Player::new("bot".to_owned());
+/// let mut rival = Player::new("rival".to_owned());
+/// for category in Category::STANDARD.iter().filter(|category| **category != Category::Chance) {
+///     player.update_score(category.score(0));
+///     rival.update_score(category.score(0));
+/// }
+/// rival.update_score(Score::Chance(29));
+/// player.dice = vec![6, 6, 1, 2, 5];
+///
+/// // holding 5 6 6 is best on average, but only 6 6 can still get to 30
+/// let mut bot = WinProbability::new(&solved);
+/// bot.observe(&[player.clone(), rival], 0);
+/// assert_eq!(bot.reroll(&player, 2), vec![3, 4, 5]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct WinProbability<'a> {
+    solved: &'a Solved,
+    /// the score and the points still to come of the opponent to beat
+    rival: Option<(u32, Outlook)>,
+    /// the spread of the opponent's points over their last few turns
+    endgame: EndgamePoints<'a>,
+    /// exact chances against the opponent, once they're in their last few
+    /// turns
+    showdown: Option<Showdown<'a>>,
+    /// the card and score the keep values are for, and the values of every
+    /// keep with one and two rolls left
+    turn: Option<(Card, u32, [Vec<f64>; 2])>,
+}
+
+impl<'a> WinProbability<'a> {
+    pub fn new(solved: &'a Solved) -> WinProbability<'a> {
+        WinProbability {
+            solved,
+            rival: None,
+            endgame: EndgamePoints::new(&solved.solver),
+            showdown: None,
+            turn: None,
+        }
+    }
+
+    /// how good it is to finish the turn with points and a card to play on
+    fn objective(&mut self, score: u32, points: u32, next: Card) -> f64 {
+        if let Some(showdown) = self.showdown.as_mut() {
+            if next.turns_left() <= EXACT_TURNS {
+                return showdown.after(next, score + points);
+            }
+        }
+        let mean = f64::from(score + points) + self.solved.solver.value(next);
+        match self.rival {
+            Some((rival_score, rival)) => {
+                let lead = f64::from(score + points) - f64::from(rival_score);
+                self.solved.outlook(next).chance_ahead(lead, &rival) + TIE_BREAK * mean
+            }
+            None => mean,
+        }
+    }
+
+    /// the best objective from scoring a hand
+    fn score_objective(&mut self, player: &Player, card: Card, hand: DiceHand) -> f64 {
+        let solver = &self.solved.solver;
+        Category::STANDARD
+            .iter()
+            .filter_map(|category| solver.after_score(card, hand, *category))
+            .map(|(points, next)| self.objective(player.score, points, next))
+            .fold(f64::MIN, f64::max)
+    }
+
+    fn keeps(&mut self, player: &Player) -> &[Vec<f64>; 2] {
+        let card = solver_card(&self.solved.solver, player);
+        let current = matches!(self.turn, Some((turn_card, score, _)) if turn_card == card && score == player.score);
+        if !current {
+            let scored: Vec<f64> = DiceHand::all()
+                .map(|hand| self.score_objective(player, card, hand))
+                .collect();
+            let keeps_0 = solver::keep_values(&scored);
+            let keeps_1 = solver::keep_values(&solver::hand_values(&keeps_0));
+            self.turn = Some((card, player.score, [keeps_0, keeps_1]));
+        }
+        &self.turn.as_ref().expect("the turn was just worked out").2
+    }
+}
+
+impl Strategy for WinProbability<'_> {
+    fn observe(&mut self, players: &[Player], current: usize) {
+        let solved = self.solved;
+        let rival = players
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| *index != current)
+            .filter_map(|(_, player)| Some((player.score, solved.solver.card(player)?)))
+            .fold(None, |best, rival| {
+                let expected =
+                    |(score, card): (u32, Card)| f64::from(score) + solved.solver.value(card);
+                match best {
+                    Some(best) if expected(best) >= expected(rival) => Some(best),
+                    _ => Some(rival),
+                }
+            });
+        self.rival = rival.map(|(score, card)| (score, solved.outlook(card)));
+        self.showdown = match rival {
+            Some((score, card)) if card.turns_left() <= EXACT_TURNS => {
+                let mut finals = vec![0.0; score as usize];
+                finals.extend(self.endgame.points(card));
+                Some(Showdown::new(solved, &finals))
+            }
+            _ => None,
+        };
+        self.turn = None;
+    }
+
+    fn reroll(&mut self, player: &Player, rolls_left: u32) -> Vec<u8> {
+        let hand = player_hand(player);
+        let keeps = &self.keeps(player)[rolls_left.min(2) as usize - 1];
+        let keep = hand
+            .keeps()
+            .iter()
+            .fold(None, |best: Option<&Keep>, keep| match best {
+                Some(best) if keeps[best.index()] >= keeps[keep.index()] => Some(best),
+                _ => Some(keep),
+            })
+            .expect("every hand can be held");
+        reroll_for(&player.dice, &keep.dice())
+    }
+
+    fn score(&mut self, player: &Player, possible_scores: &[Score]) -> Score {
+        let hand = player_hand(player);
+        let solver = &self.solved.solver;
+        let card = solver_card(solver, player);
+        best_by(possible_scores, |category| {
+            solver
+                .after_score(card, hand, category)
+                .map(|(points, next)| self.objective(player.score, points, next))
+        })
+    }
+}
+
+/// Plays to reach a target final score, taking whichever choice gives the
+/// best chance of getting there.
+#[derive(Debug, Clone)]
+pub struct Reach<'a> {
+    threshold: Threshold<'a>,
+    /// the card and score the chances are for
+    turn: Option<(Card, u32, Chances)>,
+}
+
+impl<'a> Reach<'a> {
+    pub fn new(solved: &'a Solved, target: u32) -> Reach<'a> {
+        Reach {
+            threshold: Threshold::new(solved, target),
+            turn: None,
+        }
+    }
+
+    fn chances(&mut self, player: &Player) -> &Chances {
+        let card = solver_card(&self.threshold.solved().solver, player);
+        let current = matches!(&self.turn, Some((turn_card, score, _)) if *turn_card == card && *score == player.score);
+        if !current {
+            let chances = self.threshold.turn(card, player.score);
+            self.turn = Some((card, player.score, chances));
+        }
+        &self.turn.as_ref().expect("the turn was just worked out").2
+    }
+}
+
+impl Strategy for Reach<'_> {
+    fn reroll(&mut self, player: &Player, rolls_left: u32) -> Vec<u8> {
+        let hand = player_hand(player);
+        let (keep, _) = self.chances(player).best_keep(hand, rolls_left);
+        reroll_for(&player.dice, &keep.dice())
+    }
+
+    fn score(&mut self, player: &Player, possible_scores: &[Score]) -> Score {
+        let hand = player_hand(player);
+        let chances = self.chances(player);
+        best_by(possible_scores, |category| {
+            chances.score_chance(hand, category)
+        })
+    }
+}
+
+/// The computer players that can be picked to play
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub enum Bot {
+    Greedy,
+    /// plays for the highest average score
+    Expected,
+    /// plays to beat the other players
+    WinProbability,
+    /// plays to reach a final score
+    Target(u32),
+    /// a program run with a command, which plays through the engine protocol
+    Engine(String),
+}
+
+impl Bot {
+    pub const ALL: [Bot; 3] = [Bot::Greedy, Bot::Expected, Bot::WinProbability];
+
+    /// whether the bot plays from the solved tables
+    pub fn needs_solved(&self) -> bool {
+        !matches!(self, Bot::Greedy | Bot::Engine(_))
+    }
+
+    /// The strategy the bot plays with, starting the program for an engine.
+    ///
+    /// # Panics
+    /// If the bot needs solved tables and none are passed in.
+    pub fn strategy<'a>(
+        &self,
+        solved: Option<&'a Solved>,
+    ) -> Result<Box<dyn Strategy + 'a>, EngineError> {
+        let solved =
+            || solved.unwrap_or_else(|| panic!("the {} bot needs the solved tables", self));
+        Ok(match self {
+            Bot::Greedy => Box::new(Greedy),
+            Bot::Expected => Box::new(Expected::new(&solved().solver)),
+            Bot::WinProbability => Box::new(WinProbability::new(solved())),
+            Bot::Target(target) => Box::new(Reach::new(solved(), *target)),
+            Bot::Engine(command) => Box::new(Engine::spawn(command, MOVE_TIMEOUT)?),
+        })
+    }
+}
+
+impl fmt::Display for Bot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Bot::Greedy => write!(f, "greedy"),
+            Bot::Expected => write!(f, "ev"),
+            Bot::WinProbability => write!(f, "win"),
+            Bot::Target(target) => write!(f, "target:{}", target),
+            Bot::Engine(command) => write!(f, "engine:{}", command),
+        }
+    }
+}
+
+/// The text given wasn't the name of a bot
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ParseBotError(pub String);
+
+impl fmt::Display for ParseBotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unknown bot \"{}\", use greedy, ev, win, target:<score> or engine:<command>",
+            self.0
+        )
+    }
+}
+
+impl str::FromStr for Bot {
+    type Err = ParseBotError;
+
+    fn from_str(input: &str) -> Result<Bot, ParseBotError> {
+        if let Some(command) = input.trim().strip_prefix("engine:") {
+            return match command.trim() {
+                "" => Err(ParseBotError(input.to_owned())),
+                command => Ok(Bot::Engine(command.to_owned())),
+            };
+        }
+        let name = input.trim().to_lowercase();
+        if let Some(target) = name.strip_prefix("target:") {
+            return target
+                .parse()
+                .map(Bot::Target)
+                .map_err(|_| ParseBotError(input.to_owned()));
+        }
+        Bot::ALL
+            .iter()
+            .find(|bot| bot.to_string() == name)
+            .cloned()
+            .ok_or_else(|| ParseBotError(input.to_owned()))
+    }
+}
+
+/// Plays one turn for the player: the first roll, any rerolls the strategy
+/// asks for and the score it picks.
+pub fn play_turn<S, R>(player: &mut Player, strategy: &mut S, rng: &mut R) -> Score
+where
+    S: Strategy + ?Sized,
+    R: Rng + ?Sized,
+{
+    player.roll_dice_with(rng);
+    for rolls_left in (1..ROLLS_PER_TURN).rev() {
+        let dice = strategy.reroll(player, rolls_left);
+        if dice.is_empty() {
+            break;
+        }
+        player.reroll_with(dice, rng);
+    }
+
+    let possible_scores = player.possible_scores();
+    let score = strategy.score(player, &possible_scores);
+    player.update_score(score);
+    score
+}
+
+/// Plays a complete solo game of regular yahtzee with a strategy, returning
+/// the finished player.
+///
+/// # Example
+/// ```rust
+/// use rand::rngs::StdRng;
+/// use rand::SeedableRng;
+/// use yahtzee::strategy::{self, Greedy};
+///
+/// let player = strategy::play_game("bot".to_owned(), &mut Greedy, &mut StdRng::seed_from_u64(1));
+/// assert!(player.scores.iter().filter(|score| score.category().is_some()).count() == 13);
+/// assert!(player.score > 0);
+/// ```
+pub fn play_game<S, R>(name: String, strategy: &mut S, rng: &mut R) -> Player
+where
+    S: Strategy + ?Sized,
+    R: Rng + ?Sized,
+{
+    let mut player = Player::new(name);
+    for _ in 0..player.rules.num_rounds() {
+        play_turn(&mut player, strategy, rng);
+    }
+    player.endgame();
+    player
+}
+
+/// Plays a complete game of regular yahtzee between strategies, one player
+/// for each, returning the finished players in seat order.
+///
+/// # Example
+/// ```rust
+/// use rand::rngs::StdRng;
+/// use rand::SeedableRng;
+/// use yahtzee::strategy::{self, Greedy, Strategy};
+///
+/// let mut strategies: Vec<Box<dyn Strategy>> = vec![Box::new(Greedy), Box::new(Greedy)];
+/// let players = strategy::play_match(&mut strategies, &mut StdRng::seed_from_u64(1));
+/// assert_eq!(players.len(), 2);
+/// assert_eq!(players[1].name, "seat 2");
+/// assert!(players.iter().all(|player| player.score > 0));
+/// ```
+pub fn play_match<R>(strategies: &mut [Box<dyn Strategy + '_>], rng: &mut R) -> Vec<Player>
+where
+    R: Rng + ?Sized,
+{
+    let mut players: Vec<Player> = (1..=strategies.len())
+        .map(|seat| Player::new(format!("seat {}", seat)))
+        .collect();
+    for _ in 0..Rules::default().num_rounds() {
+        for (seat, strategy) in strategies.iter_mut().enumerate() {
+            strategy.observe(&players, seat);
+            play_turn(&mut players[seat], strategy.as_mut(), rng);
+        }
+    }
+    for player in players.iter_mut() {
+        player.endgame();
+    }
+    players
+}
+
+/// Plays a complete game of regular yahtzee between strategies like
+/// `play_match`, except that each seat rolls from its own seed. Every roll
+/// comes from the seed, round and roll alone, so whichever strategy sits in
+/// a seat is dealt the same dice, as far as its holds allow.
+///
+/// # Example
+/// ```rust
+/// use yahtzee::strategy::{self, Greedy, Strategy};
+///
+/// let mut strategies: Vec<Box<dyn Strategy>> = vec![Box::new(Greedy), Box::new(Greedy)];
+/// let players = strategy::play_seeded_match(&mut strategies, &[5, 9]);
+/// assert_eq!(players, strategy::play_seeded_match(&mut strategies, &[5, 9]));
+/// let swapped = strategy::play_seeded_match(&mut strategies, &[9, 5]);
+/// assert_eq!(swapped[0].scores, players[1].scores);
+/// ```
+pub fn play_seeded_match(strategies: &mut [Box<dyn Strategy + '_>], seeds: &[u64]) -> Vec<Player> {
+    assert_eq!(strategies.len(), seeds.len(), "every seat needs a seed");
+    let rules = Rules::default();
+    let mut players: Vec<Player> = (1..=strategies.len())
+        .map(|seat| Player::new(format!("seat {}", seat)))
+        .collect();
+    for round in 1..=rules.num_rounds() {
+        for (seat, strategy) in strategies.iter_mut().enumerate() {
+            strategy.observe(&players, seat);
+            let player = &mut players[seat];
+            let seed = seeds[seat];
+            player.dice = daily::dice(seed, round, 0, &rules);
+            for roll in 1..ROLLS_PER_TURN {
+                let mut reroll = strategy.reroll(player, ROLLS_PER_TURN - roll);
+                if reroll.is_empty() {
+                    break;
+                }
+                // like the daily challenge, rerolled dice take the roll's
+                // values in order
+                reroll.sort_unstable();
+                reroll.dedup();
+                for (die, value) in reroll
+                    .into_iter()
+                    .zip(daily::dice(seed, round, roll, &rules))
+                {
+                    player.dice[die as usize - 1] = value;
+                }
+            }
+
+            let possible_scores = player.possible_scores();
+            let score = strategy.score(player, &possible_scores);
+            player.update_score(score);
+        }
+    }
+    for player in players.iter_mut() {
+        player.endgame();
+    }
+    players
+}
+
+/// the card a player's turn is played from by strategies using the solver
+fn solver_card(solver: &Solver, player: &Player) -> Card {
+    solver
+        .card(player)
+        .expect("the solver only plays regular games")
+}
+
+fn player_hand(player: &Player) -> DiceHand {
+    DiceHand::from_dice(&player.dice).expect("the solver only plays regular games")
+}
+
+/// the dice (numbered from 1) to reroll so the held values are left
+pub(crate) fn reroll_for(dice: &[u32], held: &[u32]) -> Vec<u8> {
+    let mut held = held.to_vec();
+    (1..=dice.len() as u8)
+        .filter(|die| {
+            match held
+                .iter()
+                .position(|value| *value == dice[*die as usize - 1])
+            {
+                Some(position) => {
+                    held.swap_remove(position);
+                    false
+                }
+                None => true,
+            }
+        })
+        .collect()
+}
+
+/// the possible score whose category is valued highest
+fn best_by<F>(possible_scores: &[Score], mut value: F) -> Score
+where
+    F: FnMut(Category) -> Option<f64>,
+{
+    possible_scores
+        .iter()
+        .filter_map(|score| Some((*score, value(score.category()?)?)))
+        .fold(
+            None,
+            |best: Option<(Score, f64)>, (score, value)| match best {
+                Some((_, best_value)) if best_value >= value => best,
+                _ => Some((score, value)),
+            },
+        )
+        .expect("no scores left to choose from")
+        .0
+}
@@ -0,0 +1,72 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Players whose scores count together
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct Team {
+    pub name: String,
+    /// the people on the team, in the order they take turns
+    pub members: Vec<String>,
+}
+
+/// Reasons teams can't play a game together
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum TeamError {
+    NoTeams,
+    NoMembers(String),
+    /// teams playing their own cards need the same number of players, or
+    /// the bigger team would get more turns
+    Uneven,
+}
+
+impl fmt::Display for TeamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TeamError::NoTeams => write!(f, "there are no teams"),
+            TeamError::NoMembers(team) => write!(f, "{} has no players", team),
+            TeamError::Uneven => write!(
+                f,
+                "teams need the same number of players unless they share a scorecard"
+            ),
+        }
+    }
+}
+
+/// Seats the teams' players so turns go around the teams in order, each
+/// team's players taking their turns in order.
+///
+/// # Example
+/// ```rust
+/// use yahtzee::team::{self, Team, TeamError};
+///
+/// let team = |name: &str, members: &[&str]| Team {
+///     name: name.to_owned(),
+///     members: members.iter().map(|member| member.to_string()).collect(),
+/// };
+/// let teams = [team("Red", &["Ann", "Bob"]), team("Blue", &["Cat", "Dan"])];
+/// assert_eq!(team::seating(&teams).unwrap(), vec!["Ann", "Cat", "Bob", "Dan"]);
+///
+/// let uneven = [team("Red", &["Ann", "Bob"]), team("Blue", &["Cat"])];
+/// assert_eq!(team::seating(&uneven), Err(TeamError::Uneven));
+/// ```
+pub fn seating(teams: &[Team]) -> Result<Vec<String>, TeamError> {
+    check(teams)?;
+    let size = teams[0].members.len();
+    if teams.iter().any(|team| team.members.len() != size) {
+        return Err(TeamError::Uneven);
+    }
+    Ok((0..size)
+        .flat_map(|seat| teams.iter().map(move |team| team.members[seat].clone()))
+        .collect())
+}
+
+/// checks every team has someone to play for it
+pub fn check(teams: &[Team]) -> Result<(), TeamError> {
+    if teams.is_empty() {
+        return Err(TeamError::NoTeams);
+    }
+    match teams.iter().find(|team| team.members.is_empty()) {
+        Some(team) => Err(TeamError::NoMembers(team.name.clone())),
+        None => Ok(()),
+    }
+}
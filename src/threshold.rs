@@ -0,0 +1,211 @@
+use crate::endgame::EXACT_TURNS;
+use crate::hand::{self, DiceHand, Keep};
+use crate::score::Category;
+use crate::solver::{self, Card, Solved};
+use crate::strategy::TIE_BREAK;
+use crate::transcript::Decision;
+use std::collections::HashMap;
+
+/// Plays to reach a target final score rather than for the highest average.
+///
+/// Chances are exact over the last few turns of a game. Before that, the
+/// chance of getting the points still needed after the current turn is
+/// estimated from their spread, and the current turn is played exactly
+/// against that estimate.
+///
+/// # Example
+/// ```rust
+/// use yahtzee::score::Category;
+/// use yahtzee::solver::{Card, Solved, Solver, Spread};
+/// use yahtzee::threshold::Threshold;
+///
+/// let solver = Solver::endgame(1);
+/// let spread = Spread::new(&solver);
+/// let solved = Solved { solver, spread };
+/// let mut threshold = Threshold::new(&solved, 230);
+///
+/// // only chance is left, and 200 points are already in
+/// let card = Card { filled: !(1 << Category::Chance.index()) & 0x1fff, upper: 0, yahtzee_bonus: false };
+/// assert_eq!(threshold.chance(card, 230), 1.0);
+/// assert!(threshold.chance(card, 200) < threshold.chance(card, 205));
+/// assert!(threshold.chance(card, 199) < 1e-9);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Threshold<'a> {
+    solved: &'a Solved,
+    target: u32,
+    /// chances already worked out for a card and the points still needed
+    chances: HashMap<(Card, u32), f64>,
+}
+
+/// The chance of reaching the target after every choice in one turn.
+#[derive(Debug, Clone)]
+pub struct Chances {
+    /// `hands[rolls][hand]` is the chance from having a hand with rolls left
+    hands: [Vec<f64>; 3],
+    /// `keeps[rolls][keep]` is the chance from holding dice and rolling the
+    /// rest, with rolls left after that roll
+    keeps: [Vec<f64>; 2],
+    /// the chance from scoring each hand in each category
+    scores: Vec<[Option<f64>; Category::COUNT]>,
+}
+
+impl<'a> Threshold<'a> {
+    pub fn new(solved: &'a Solved, target: u32) -> Threshold<'a> {
+        Threshold {
+            solved,
+            target,
+            chances: HashMap::new(),
+        }
+    }
+
+    pub fn target(&self) -> u32 {
+        self.target
+    }
+
+    pub fn solved(&self) -> &'a Solved {
+        self.solved
+    }
+
+    /// the chance of reaching the target from the start of a turn on a card
+    /// with points already scored
+    pub fn chance(&mut self, card: Card, score: u32) -> f64 {
+        self.reach(card, self.target.saturating_sub(score))
+    }
+
+    /// works out the chance after every hold and score for a turn
+    pub fn turn(&mut self, card: Card, score: u32) -> Chances {
+        let needed = self.target.saturating_sub(score);
+        let solved = self.solved;
+        let scores: Vec<[Option<f64>; Category::COUNT]> = DiceHand::all()
+            .map(|hand| {
+                let mut scores = [None; Category::COUNT];
+                for category in Category::STANDARD.iter() {
+                    scores[category.index()] = solved
+                        .solver
+                        .after_score(card, hand, *category)
+                        .map(|(points, next)| self.after(next, points, needed));
+                }
+                scores
+            })
+            .collect();
+
+        let scored: Vec<f64> = scores
+            .iter()
+            .map(|scores| scores.iter().flatten().copied().fold(f64::MIN, f64::max))
+            .collect();
+        let keeps_0 = solver::keep_values(&scored);
+        let hands_1 = solver::hand_values(&keeps_0);
+        let keeps_1 = solver::keep_values(&hands_1);
+        let hands_2 = solver::hand_values(&keeps_1);
+        Chances {
+            hands: [scored, hands_1, hands_2],
+            keeps: [keeps_0, keeps_1],
+            scores,
+        }
+    }
+
+    /// the chance of scoring at least `needed` more points from the start of
+    /// a turn on a card
+    fn reach(&mut self, card: Card, needed: u32) -> f64 {
+        if needed == 0 {
+            return 1.0;
+        }
+        if card.turns_left() == 0 {
+            return 0.0;
+        }
+        if card.turns_left() > EXACT_TURNS {
+            return self.solved.outlook(card).chance_at_least(f64::from(needed));
+        }
+        if let Some(chance) = self.chances.get(&(card, needed)) {
+            return *chance;
+        }
+
+        let turn = self.turn(card, self.target - needed);
+        let chance = hand::first_roll()
+            .iter()
+            .map(|(hand, probability)| probability * turn.hands[2][hand.index()])
+            .sum::<f64>()
+            .min(1.0);
+        self.chances.insert((card, needed), chance);
+        chance
+    }
+
+    /// the chance of reaching the target after scoring points and moving on
+    /// to a card, with a share of the expected points to settle ties
+    fn after(&mut self, next: Card, points: u32, needed: u32) -> f64 {
+        let expected = f64::from(points) + self.solved.solver.value(next);
+        self.reach(next, needed.saturating_sub(points)) + TIE_BREAK * expected
+    }
+}
+
+impl Chances {
+    /// the chance from having a hand with rolls left, playing on as well as
+    /// possible
+    pub fn hand_chance(&self, hand: DiceHand, rolls_left: u32) -> f64 {
+        self.hands[rolls_left.min(2) as usize][hand.index()].min(1.0)
+    }
+
+    /// the chance from holding dice and rolling the rest, when there are
+    /// `rolls_left` rolls before that roll
+    ///
+    /// # Panics
+    /// If there are no rolls left.
+    pub fn keep_chance(&self, keep: Keep, rolls_left: u32) -> f64 {
+        assert!(rolls_left > 0, "no rolls left to hold dice for");
+        self.keeps[rolls_left.min(2) as usize - 1][keep.index()].min(1.0)
+    }
+
+    /// the best dice to hold from a hand with rolls left and the chance from
+    /// holding them
+    pub fn best_keep(&self, hand: DiceHand, rolls_left: u32) -> (Keep, f64) {
+        let keeps = &self.keeps[rolls_left.clamp(1, 2) as usize - 1];
+        let keep = hand
+            .keeps()
+            .iter()
+            .copied()
+            .fold(None, |best: Option<Keep>, keep| match best {
+                Some(best) if keeps[best.index()] >= keeps[keep.index()] => Some(best),
+                _ => Some(keep),
+            })
+            .expect("every hand can be held");
+        (keep, self.keep_chance(keep, rolls_left))
+    }
+
+    /// the chance from scoring a hand in a category, or None if it can't be
+    /// scored there
+    pub fn score_chance(&self, hand: DiceHand, category: Category) -> Option<f64> {
+        self.scores[hand.index()][category.index()].map(|chance| chance.min(1.0))
+    }
+
+    /// The best decision for a hand with rolls left and the chance from it,
+    /// preferring to score when holding is no better, or None if there's
+    /// nothing left to score.
+    pub fn best_decision(&self, hand: DiceHand, rolls_left: u32) -> Option<(Decision, f64)> {
+        let (category, score_chance) = self.best_score(hand)?;
+        let scored = self.scores[hand.index()][category.index()]?;
+        Some(match rolls_left {
+            0 => (Decision::Score(category), score_chance),
+            _ => match self.best_keep(hand, rolls_left) {
+                (keep, _) if self.keeps[rolls_left.min(2) as usize - 1][keep.index()] > scored => (
+                    Decision::Hold(keep.dice()),
+                    self.keep_chance(keep, rolls_left),
+                ),
+                _ => (Decision::Score(category), score_chance),
+            },
+        })
+    }
+
+    /// the best category to score a hand in and the chance from scoring it
+    pub fn best_score(&self, hand: DiceHand) -> Option<(Category, f64)> {
+        let scores = &self.scores[hand.index()];
+        Category::STANDARD
+            .iter()
+            .filter_map(|category| Some((*category, scores[category.index()]?)))
+            .fold(None, |best, (category, chance)| match best {
+                Some((_, best_chance)) if best_chance >= chance => best,
+                _ => Some((category, chance)),
+            })
+            .map(|(category, chance)| (category, chance.min(1.0)))
+    }
+}
@@ -0,0 +1,376 @@
+use crate::engine::EngineError;
+use crate::player::Player;
+use crate::probability;
+use crate::solver::Solved;
+use crate::strategy::{self, Bot, Strategy};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::cmp::Ordering;
+use std::io::{self, Write};
+
+/// standard normal quantile for a 95% confidence interval
+const Z_95: f64 = 1.959_963_985;
+
+/// An average with the margin of its 95% confidence interval
+///
+/// # Example
+/// ```rust
+/// use yahtzee::tournament::Estimate;
+///
+/// let samples: Vec<f64> = [1.0, 0.0, 1.0, 1.0, 0.5, 1.0, 0.5, 1.0].iter().cycle().take(32).copied().collect();
+/// let estimate = Estimate::from_samples(&samples);
+/// assert_eq!(estimate.mean, 0.75);
+/// assert!(estimate.low() > 0.5 && estimate.high() < 1.0);
+/// assert!(estimate.p_value(0.5) < 0.05);
+/// assert!(estimate.p_value(0.75) > 0.99);
+/// ```
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Estimate {
+    pub mean: f64,
+    pub margin: f64,
+}
+
+impl Estimate {
+    /// the mean of independent samples, with an infinite margin when there
+    /// are too few to tell how they vary
+    pub fn from_samples(samples: &[f64]) -> Estimate {
+        let count = samples.len() as f64;
+        let mean = samples.iter().sum::<f64>() / count.max(1.0);
+        if samples.len() < 2 {
+            return Estimate {
+                mean,
+                margin: f64::INFINITY,
+            };
+        }
+        let variance = samples
+            .iter()
+            .map(|sample| (sample - mean).powi(2))
+            .sum::<f64>()
+            / (count - 1.0);
+        Estimate {
+            mean,
+            margin: Z_95 * (variance / count).sqrt(),
+        }
+    }
+
+    pub fn low(&self) -> f64 {
+        self.mean - self.margin
+    }
+
+    pub fn high(&self) -> f64 {
+        self.mean + self.margin
+    }
+
+    /// the two-sided p-value of the true mean being `value`
+    pub fn p_value(&self, value: f64) -> f64 {
+        let error = self.margin / Z_95;
+        if error == 0.0 {
+            return if self.mean == value { 1.0 } else { 0.0 };
+        }
+        probability::erfc((self.mean - value).abs() / (error * 2f64.sqrt()))
+    }
+}
+
+/// How a bot's final scores were spread
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct ScoreSummary {
+    pub games: usize,
+    pub mean: f64,
+    pub standard_deviation: f64,
+    /// the score a tenth of games finished below
+    pub p10: u32,
+    pub median: u32,
+    /// the score a tenth of games finished above
+    pub p90: u32,
+}
+
+impl ScoreSummary {
+    pub fn new(mut scores: Vec<u32>) -> ScoreSummary {
+        scores.sort_unstable();
+        let games = scores.len();
+        let mean = scores.iter().map(|score| f64::from(*score)).sum::<f64>() / games.max(1) as f64;
+        let variance = scores
+            .iter()
+            .map(|score| (f64::from(*score) - mean).powi(2))
+            .sum::<f64>()
+            / games.max(1) as f64;
+        let percentile = |percent: usize| match games {
+            0 => 0,
+            _ => scores[(games - 1) * percent / 100],
+        };
+        ScoreSummary {
+            games,
+            mean,
+            standard_deviation: variance.sqrt(),
+            p10: percentile(10),
+            median: percentile(50),
+            p90: percentile(90),
+        }
+    }
+}
+
+/// The games played between two bots
+#[derive(Debug, PartialEq, Clone)]
+pub struct Pairing {
+    /// the bots, by their index in the tournament
+    pub first: usize,
+    pub second: usize,
+    /// the scores of the first and second bot in each game. Games come in
+    /// pairs played from the same seeds, with the first bot seated first
+    /// and then second.
+    pub scores: Vec<(u32, u32)>,
+}
+
+impl Pairing {
+    /// the first bot's share of the wins in each pair of games, ties
+    /// counting as half a win
+    fn pair_wins(&self) -> Vec<f64> {
+        self.scores
+            .chunks(2)
+            .map(|games| {
+                let wins: f64 = games
+                    .iter()
+                    .map(|(first, second)| win(*first, *second))
+                    .sum();
+                wins / games.len() as f64
+            })
+            .collect()
+    }
+
+    /// The share of games won by the first bot.
+    ///
+    /// Each pair of games is one sample, since both were dealt the same dice.
+    pub fn win_rate(&self) -> Estimate {
+        Estimate::from_samples(&self.pair_wins())
+    }
+
+    /// how many more points the first bot scored a game on average
+    pub fn score_difference(&self) -> Estimate {
+        let differences: Vec<f64> = self
+            .scores
+            .chunks(2)
+            .map(|games| {
+                let difference: f64 = games
+                    .iter()
+                    .map(|(first, second)| f64::from(*first) - f64::from(*second))
+                    .sum();
+                difference / games.len() as f64
+            })
+            .collect();
+        Estimate::from_samples(&differences)
+    }
+}
+
+/// One bot's place in the tournament
+#[derive(Debug, PartialEq, Clone)]
+pub struct Standing {
+    /// the bot, by its index in the tournament
+    pub bot: usize,
+    /// the share of all its games the bot won
+    pub win_rate: Estimate,
+    pub scores: ScoreSummary,
+}
+
+/// Every bot played against every other, head to head.
+///
+/// # Example
+/// ```rust
+/// use yahtzee::strategy::Bot;
+/// use yahtzee::tournament::Tournament;
+///
+/// // the same bot dealt the same dice in both seats can only draw
+/// let tournament = Tournament::play(vec![Bot::Greedy, Bot::Greedy], 20, 1, None).unwrap();
+/// let pairing = &tournament.pairings[0];
+/// assert_eq!(pairing.scores.len(), 20);
+/// assert_eq!(pairing.win_rate().mean, 0.5);
+/// assert_eq!(pairing.win_rate().margin, 0.0);
+/// assert_eq!(pairing.score_difference().mean, 0.0);
+///
+/// let standings = tournament.standings();
+/// assert_eq!(standings.len(), 2);
+/// assert_eq!(standings[0].scores, standings[1].scores);
+///
+/// let mut csv = Vec::new();
+/// tournament.write_csv(&mut csv).unwrap();
+/// let csv = String::from_utf8(csv).unwrap();
+/// assert!(csv.starts_with("rank,bot,games,win_rate,"));
+/// assert_eq!(csv.lines().count(), 3);
+/// ```
+#[derive(Debug, PartialEq, Clone)]
+pub struct Tournament {
+    pub bots: Vec<Bot>,
+    /// every bot against every later one
+    pub pairings: Vec<Pairing>,
+}
+
+impl Tournament {
+    /// Plays `games` games between every pair of bots, half with each bot
+    /// seated first, rounding an odd number of games up.
+    ///
+    /// Each pair of games is dealt dice from one pair of seeds, with the bots
+    /// swapping seats between them, and every pairing uses the same seeds.
+    /// Luck with the dice then mostly cancels out of the comparisons, so far
+    /// fewer games are needed to tell bots apart.
+    ///
+    /// # Panics
+    /// If a bot needs solved tables and none are passed in.
+    pub fn play(
+        bots: Vec<Bot>,
+        games: u32,
+        seed: u64,
+        solved: Option<&Solved>,
+    ) -> Result<Tournament, EngineError> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let seeds: Vec<[u64; 2]> = (0..games.div_ceil(2))
+            .map(|_| [rng.gen(), rng.gen()])
+            .collect();
+
+        let mut pairings = Vec::new();
+        for first in 0..bots.len() {
+            for second in first + 1..bots.len() {
+                let mut scores = Vec::with_capacity(seeds.len() * 2);
+                for seeds in seeds.iter() {
+                    let players = play_game(&bots[first], &bots[second], seeds, solved)?;
+                    scores.push((players[0].score, players[1].score));
+                    let players = play_game(&bots[second], &bots[first], seeds, solved)?;
+                    scores.push((players[1].score, players[0].score));
+                }
+                pairings.push(Pairing {
+                    first,
+                    second,
+                    scores,
+                });
+            }
+        }
+        Ok(Tournament { bots, pairings })
+    }
+
+    /// the share of games a bot won against another, or None if they didn't
+    /// play each other
+    pub fn win_rate_against(&self, bot: usize, other: usize) -> Option<Estimate> {
+        self.pairings.iter().find_map(|pairing| {
+            let estimate = pairing.win_rate();
+            match (pairing.first, pairing.second) {
+                (first, second) if (first, second) == (bot, other) => Some(estimate),
+                (first, second) if (first, second) == (other, bot) => Some(Estimate {
+                    mean: 1.0 - estimate.mean,
+                    margin: estimate.margin,
+                }),
+                _ => None,
+            }
+        })
+    }
+
+    /// every bot ranked by the share of its games it won, then by its
+    /// average score
+    pub fn standings(&self) -> Vec<Standing> {
+        let mut standings: Vec<Standing> = (0..self.bots.len())
+            .map(|bot| {
+                let mut wins = Vec::new();
+                let mut scores = Vec::new();
+                for pairing in self.pairings.iter() {
+                    if pairing.first == bot {
+                        wins.extend(pairing.pair_wins());
+                        scores.extend(pairing.scores.iter().map(|(score, _)| *score));
+                    } else if pairing.second == bot {
+                        wins.extend(pairing.pair_wins().iter().map(|wins| 1.0 - wins));
+                        scores.extend(pairing.scores.iter().map(|(_, score)| *score));
+                    }
+                }
+                Standing {
+                    bot,
+                    win_rate: Estimate::from_samples(&wins),
+                    scores: ScoreSummary::new(scores),
+                }
+            })
+            .collect();
+        standings.sort_by(|a, b| {
+            b.win_rate
+                .mean
+                .total_cmp(&a.win_rate.mean)
+                .then(b.scores.mean.total_cmp(&a.scores.mean))
+        });
+        standings
+    }
+
+    /// writes the standings as CSV, with each bot's win rate against every
+    /// other in the last columns
+    pub fn write_csv<W: Write>(&self, mut out: W) -> io::Result<()> {
+        let standings = self.standings();
+        let mut header: Vec<String> = [
+            "rank",
+            "bot",
+            "games",
+            "win_rate",
+            "win_rate_low",
+            "win_rate_high",
+            "mean_score",
+            "score_sd",
+            "p10",
+            "median",
+            "p90",
+        ]
+        .iter()
+        .map(|column| column.to_string())
+        .collect();
+        header.extend(
+            standings
+                .iter()
+                .map(|standing| csv_field(&format!("vs {}", self.bots[standing.bot]))),
+        );
+        writeln!(out, "{}", header.join(","))?;
+
+        for (rank, standing) in standings.iter().enumerate() {
+            let scores = &standing.scores;
+            let mut row = vec![
+                (rank + 1).to_string(),
+                csv_field(&self.bots[standing.bot].to_string()),
+                scores.games.to_string(),
+                format!("{:.4}", standing.win_rate.mean),
+                format!("{:.4}", standing.win_rate.low()),
+                format!("{:.4}", standing.win_rate.high()),
+                format!("{:.2}", scores.mean),
+                format!("{:.2}", scores.standard_deviation),
+                scores.p10.to_string(),
+                scores.median.to_string(),
+                scores.p90.to_string(),
+            ];
+            row.extend(standings.iter().map(|other| {
+                self.win_rate_against(standing.bot, other.bot)
+                    .map_or(String::new(), |estimate| format!("{:.4}", estimate.mean))
+            }));
+            writeln!(out, "{}", row.join(","))?;
+        }
+        Ok(())
+    }
+}
+
+/// a share of a win for scoring `score` against `other`
+fn win(score: u32, other: u32) -> f64 {
+    match score.cmp(&other) {
+        Ordering::Greater => 1.0,
+        Ordering::Equal => 0.5,
+        Ordering::Less => 0.0,
+    }
+}
+
+/// plays one seeded game between two bots in the order given
+fn play_game(
+    first: &Bot,
+    second: &Bot,
+    seeds: &[u64; 2],
+    solved: Option<&Solved>,
+) -> Result<Vec<Player>, EngineError> {
+    let mut strategies: Vec<Box<dyn Strategy>> =
+        vec![first.strategy(solved)?, second.strategy(solved)?];
+    Ok(strategy::play_seeded_match(&mut strategies, seeds))
+}
+
+/// a value quoted if it would otherwise break up a CSV row
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_owned()
+    }
+}
@@ -0,0 +1,131 @@
+use crate::analysis::{self, AnalysisError, Review};
+use crate::game::ROLLS_PER_TURN;
+use crate::player::Player;
+use crate::score::Category;
+use crate::solver::Solver;
+use crate::transcript::{Decision, Move};
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+/// regret small enough for an answer to count as the best one
+const CORRECT: f64 = 1e-6;
+
+/// A moment partway through a game to practise a decision on
+#[derive(Debug, PartialEq, Clone)]
+pub struct Position {
+    /// the scorecard and dice
+    pub player: Player,
+    pub round: usize,
+    pub rolls_left: u32,
+}
+
+impl Position {
+    /// Deals a random position: some boxes already filled from rolls of the
+    /// dice, a fresh roll and some number of rolls left.
+    ///
+    /// # Example
+    /// ```rust
+    /// use rand::rngs::StdRng;
+    /// use rand::SeedableRng;
+    /// use yahtzee::trainer::Position;
+    ///
+    /// let mut rng = StdRng::seed_from_u64(7);
+    /// for _ in 0..20 {
+    ///     let position = Position::random(&mut rng);
+    ///     assert!(position.rolls_left <= 2);
+    ///     assert_eq!(position.player.scores.iter().filter(|score| score.category().is_some()).count(), position.round - 1);
+    ///     assert!(!position.player.possible_scores().is_empty());
+    /// }
+    /// ```
+    pub fn random<R: Rng + ?Sized>(rng: &mut R) -> Position {
+        let mut player = Player::new("trainee".to_owned());
+        let num_filled = rng.gen_range(0, Category::STANDARD.len());
+        let mut categories = Category::STANDARD.to_vec();
+        categories.shuffle(rng);
+
+        for category in categories.into_iter().take(num_filled) {
+            // the best of a few rolls looks more like a real scorecard than one
+            let score = (0..ROLLS_PER_TURN)
+                .filter_map(|_| {
+                    player.roll_dice_with(rng);
+                    player.possible_score(category)
+                })
+                .max_by_key(|score| score.value())
+                .expect("the category is open");
+            player.update_score(score);
+        }
+
+        player.roll_dice_with(rng);
+        Position {
+            player,
+            round: num_filled + 1,
+            rolls_left: rng.gen_range(0, ROLLS_PER_TURN),
+        }
+    }
+
+    /// grades a decision against the best one for the position
+    pub fn grade(&self, solver: &Solver, decision: Decision) -> Result<Review, AnalysisError> {
+        let card = solver
+            .card(&self.player)
+            .expect("positions are dealt with regular rules");
+        let played = Move {
+            player: 0,
+            round: self.round,
+            dice: self.player.dice.clone(),
+            rolls_left: self.rolls_left,
+            decision,
+        };
+        analysis::review(&solver.turn(card), 0, &played)
+    }
+}
+
+/// Running totals for a training session
+///
+/// # Example
+/// ```rust
+/// use yahtzee::analysis::Review;
+/// use yahtzee::score::Category;
+/// use yahtzee::trainer::Session;
+/// use yahtzee::transcript::{Decision, Move};
+///
+/// let played = Move { player: 0, round: 1, dice: vec![6; 5], rolls_left: 0, decision: Decision::Score(Category::Sixes) };
+/// let mut review = Review { index: 0, played, value: 230.0, best: Decision::Score(Category::Yahtzee), best_value: 260.0 };
+/// let mut session = Session::default();
+/// session.record(&review);
+/// review.value = 260.0;
+/// session.record(&review);
+/// assert_eq!(session.answered, 2);
+/// assert_eq!(session.accuracy(), 0.5);
+/// assert_eq!(session.regret, 30.0);
+/// ```
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub struct Session {
+    pub answered: u32,
+    /// answers as good as the best choice
+    pub correct: u32,
+    /// expected points given up over every answer
+    pub regret: f64,
+}
+
+impl Session {
+    pub fn record(&mut self, review: &Review) {
+        self.answered += 1;
+        if is_correct(review) {
+            self.correct += 1;
+        }
+        self.regret += review.regret();
+    }
+
+    /// the fraction of answers that were the best choice
+    pub fn accuracy(&self) -> f64 {
+        if self.answered == 0 {
+            return 0.0;
+        }
+        f64::from(self.correct) / f64::from(self.answered)
+    }
+}
+
+/// whether a reviewed answer was as good as the best choice
+pub fn is_correct(review: &Review) -> bool {
+    review.regret() < CORRECT
+}
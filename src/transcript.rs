@@ -0,0 +1,64 @@
+use crate::rules::Rules;
+use crate::score::Category;
+use crate::storage;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::{fmt, io};
+
+/// file in the data directory the last finished game's transcript is saved to
+pub const TRANSCRIPT_FILE: &str = "last_game.json";
+
+/// A choice made by a player during their turn
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub enum Decision {
+    /// the values of the dice held before rerolling the rest
+    Hold(Vec<u32>),
+    Score(Category),
+}
+
+impl fmt::Display for Decision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Decision::Hold(dice) if dice.is_empty() => write!(f, "reroll everything"),
+            Decision::Hold(dice) => {
+                let dice: Vec<String> = dice.iter().map(u32::to_string).collect();
+                write!(f, "hold {}", dice.join(" "))
+            }
+            Decision::Score(category) => write!(f, "score {}", category),
+        }
+    }
+}
+
+/// One decision and what the player was looking at when they made it
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct Move {
+    /// index of the player in the game
+    pub player: usize,
+    pub round: usize,
+    pub dice: Vec<u32>,
+    pub rolls_left: u32,
+    pub decision: Decision,
+}
+
+/// Every decision made in a game, in order, which is enough to replay it.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct Transcript {
+    pub rules: Rules,
+    pub players: Vec<String>,
+    pub moves: Vec<Move>,
+}
+
+impl Transcript {
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        storage::save_json(path, self)
+    }
+
+    pub fn load(path: &Path) -> io::Result<Transcript> {
+        storage::load_json::<Option<Transcript>>(path)?.ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("no transcript at {}", path.display()),
+            )
+        })
+    }
+}
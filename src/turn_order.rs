@@ -0,0 +1,157 @@
+use crate::rules::Rules;
+use rand::distributions::{Distribution, Uniform};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use std::{fmt, str};
+
+/// How the order players take their turns in is picked
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum TurnOrder {
+    /// the order the players were entered in
+    #[default]
+    Entered,
+    /// a random order, drawn from the seed if there is one
+    Shuffled(Option<u64>),
+    /// everyone rolls the dice and the highest total goes first, with the
+    /// rest following on from them in the order they were entered
+    RollOff,
+}
+
+impl fmt::Display for TurnOrder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TurnOrder::Entered => write!(f, "entered"),
+            TurnOrder::Shuffled(None) => write!(f, "shuffle"),
+            TurnOrder::Shuffled(Some(seed)) => write!(f, "shuffle:{}", seed),
+            TurnOrder::RollOff => write!(f, "roll-off"),
+        }
+    }
+}
+
+/// The text given wasn't the name of a turn order
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ParseTurnOrderError(pub String);
+
+impl fmt::Display for ParseTurnOrderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unknown turn order \"{}\", use entered, shuffle, shuffle:<seed> or roll-off",
+            self.0
+        )
+    }
+}
+
+impl str::FromStr for TurnOrder {
+    type Err = ParseTurnOrderError;
+
+    fn from_str(input: &str) -> Result<TurnOrder, ParseTurnOrderError> {
+        let name = input.trim().to_lowercase();
+        if let Some(seed) = name.strip_prefix("shuffle:") {
+            return seed
+                .parse()
+                .map(|seed| TurnOrder::Shuffled(Some(seed)))
+                .map_err(|_| ParseTurnOrderError(input.to_owned()));
+        }
+        match name.as_str() {
+            "entered" => Ok(TurnOrder::Entered),
+            "shuffle" => Ok(TurnOrder::Shuffled(None)),
+            "roll-off" => Ok(TurnOrder::RollOff),
+            _ => Err(ParseTurnOrderError(input.to_owned())),
+        }
+    }
+}
+
+/// Players in a random order, always the same for the same seed.
+///
+/// # Example
+/// ```rust
+/// use yahtzee::turn_order;
+///
+/// let mut order = turn_order::shuffled(5, 7);
+/// assert_eq!(order, turn_order::shuffled(5, 7));
+/// order.sort_unstable();
+/// assert_eq!(order, vec![0, 1, 2, 3, 4]);
+/// ```
+pub fn shuffled(players: usize, seed: u64) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..players).collect();
+    order.shuffle(&mut StdRng::seed_from_u64(seed));
+    order
+}
+
+/// Everyone rolling the dice to see who goes first, the players tied on
+/// the highest total rolling again until one of them is ahead.
+///
+/// # Example
+/// ```rust
+/// use rand::rngs::StdRng;
+/// use rand::SeedableRng;
+/// use yahtzee::rules::Rules;
+/// use yahtzee::turn_order::RollOff;
+///
+/// let mut rng = StdRng::seed_from_u64(2);
+/// let roll_off = RollOff::roll(3, &Rules::default(), &mut rng);
+/// assert_eq!(roll_off.rounds[0].len(), 3);
+///
+/// let starter = roll_off.starter();
+/// let total = |dice: &Vec<u32>| dice.iter().sum::<u32>();
+/// let last = roll_off.rounds.last().unwrap();
+/// let best = last.iter().find(|(player, _)| *player == starter).unwrap();
+/// assert!(last.iter().all(|(player, dice)| *player == starter || total(dice) < total(&best.1)));
+///
+/// let order = roll_off.order(3);
+/// assert_eq!(order[0], starter);
+/// assert_eq!(order[1], (starter + 1) % 3);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct RollOff {
+    /// each round's players and the dice they rolled
+    pub rounds: Vec<Vec<(usize, Vec<u32>)>>,
+}
+
+impl RollOff {
+    pub fn roll<R: Rng + ?Sized>(players: usize, rules: &Rules, rng: &mut R) -> RollOff {
+        let die = Uniform::from(1..=rules.num_faces);
+        let mut rolling: Vec<usize> = (0..players).collect();
+        let mut rounds = Vec::new();
+        while !rolling.is_empty() {
+            let round: Vec<(usize, Vec<u32>)> = rolling
+                .iter()
+                .map(|player| {
+                    let dice = (0..rules.num_dice).map(|_| die.sample(rng)).collect();
+                    (*player, dice)
+                })
+                .collect();
+            let best = round.iter().map(|(_, dice)| total(dice)).max();
+            rolling = round
+                .iter()
+                .filter(|(_, dice)| Some(total(dice)) == best)
+                .map(|(player, _)| *player)
+                .collect();
+            rounds.push(round);
+            if rolling.len() == 1 {
+                break;
+            }
+        }
+        RollOff { rounds }
+    }
+
+    /// the player who rolled the highest total in the last round
+    pub fn starter(&self) -> usize {
+        self.rounds
+            .last()
+            .and_then(|round| round.iter().max_by_key(|(_, dice)| total(dice)))
+            .map_or(0, |(player, _)| *player)
+    }
+
+    /// the starter and then everyone after them in the order entered
+    pub fn order(&self, players: usize) -> Vec<usize> {
+        let starter = self.starter().min(players.saturating_sub(1));
+        (starter..players).chain(0..starter).collect()
+    }
+}
+
+fn total(dice: &[u32]) -> u32 {
+    dice.iter().sum()
+}